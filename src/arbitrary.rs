@@ -0,0 +1,54 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! arbitrary support.
+//!
+//! These are only available when using the `arbitrary` feature flag.
+
+use core::mem::size_of;
+
+use ::arbitrary::{Arbitrary, Result, Unstructured};
+
+use crate::bitmap::Bitmap;
+use crate::types::{BitOps, Bits, BitsImpl};
+
+/// Reads the bitmap as bytes in the layout of [`Bitmap::to_le_bytes`], with
+/// any bits past the end of the bitmap ignored.
+impl<'a, const SIZE: usize> Arbitrary<'a> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut bytes = <BitsImpl<SIZE> as Bits>::Store::to_le_bytes(&Default::default());
+        u.fill_buffer(bytes.as_mut())?;
+        Ok(Self::from_le_bytes(bytes))
+    }
+
+    #[inline]
+    fn size_hint(_depth: usize) -> (usize, Option<usize>) {
+        let size = size_of::<<BitsImpl<SIZE> as Bits>::Store>();
+        (size, Some(size))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn arbitrary() {
+        let mut u = Unstructured::new(&[0b1010_0101, 0xff, 0x12]);
+        let bitmap = Bitmap::<12>::arbitrary(&mut u).unwrap();
+        assert_eq!(bitmap, Bitmap::from_value(0b1111_1010_0101));
+        assert_eq!(u.len(), 1);
+        assert_eq!(Bitmap::<12>::size_hint(0), (2, Some(2)));
+    }
+
+    #[test]
+    fn exhausted() {
+        let mut u = Unstructured::new(&[0xff]);
+        let bitmap = Bitmap::<300>::arbitrary(&mut u).unwrap();
+        assert_eq!(bitmap, Bitmap::from_range(0..8));
+    }
+}
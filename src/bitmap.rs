@@ -2,11 +2,15 @@
 // License, v. 2.0. If a copy of the MPL was not distributed with this
 // file, You can obtain one at http://mozilla.org/MPL/2.0/.
 
-use core::fmt::{Debug, Error, Formatter};
+use core::fmt::{Binary, Debug, Display, Formatter, LowerHex, Octal, UpperHex};
 use core::hash::{Hash, Hasher};
-use core::mem::{size_of, MaybeUninit};
+use core::iter::{FusedIterator, Product, Sum};
+use core::marker::PhantomData;
+use core::mem::size_of;
 use core::ops::*;
+use core::str::FromStr;
 
+use crate::error::Error;
 use crate::types::{BitOps, Bits, BitsImpl};
 
 /// A compact array of bits.
@@ -16,6 +20,20 @@ use crate::types::{BitOps, Bits, BitsImpl};
 /// `bool` is used. If the size exceeds 128, an array of `u128` will be used,
 /// sized as appropriately. The maximum supported size is currently 1024,
 /// represented by an array `[u128; 8]`.
+///
+/// A bitmap is guaranteed to have the same memory layout as its backing
+/// store, so it can be shared with other languages as that type, eg. a
+/// `uint16_t` for a `Bitmap<10>` or a `uint64_t[4]` for a `Bitmap<256>` on a
+/// little endian target. The bits above index `SIZE - 1` in the store must
+/// always be zero.
+// zerocopy's derives check the layout of the backing store for us. Its
+// `FromBytes` is left out, because the store accepts bit patterns with bits
+// set past the end of the bitmap.
+#[cfg_attr(
+    feature = "zerocopy",
+    derive(::zerocopy::FromZeroes, ::zerocopy::AsBytes, ::zerocopy::Unaligned)
+)]
+#[repr(transparent)]
 pub struct Bitmap<const SIZE: usize>
 where
     BitsImpl<{ SIZE }>: Bits,
@@ -28,7 +46,7 @@ where
     BitsImpl<{ SIZE }>: Bits,
 {
     fn clone(&self) -> Self {
-        Bitmap::from_value(self.data)
+        *self
     }
 }
 
@@ -69,37 +87,222 @@ where
 impl<const SIZE: usize> PartialOrd for Bitmap<{ SIZE }>
 where
     BitsImpl<{ SIZE }>: Bits,
-    <BitsImpl<{ SIZE }> as Bits>::Store: PartialOrd,
 {
     fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
-        self.as_value().partial_cmp(other.as_value())
+        Some(self.cmp(other))
     }
 }
 
+/// Bitmaps are ordered as unsigned integers, where the bit at index `n` is
+/// worth `2^n`, regardless of the type of their backing store.
+///
+/// That is, the bitmap with the highest `true` bit which isn't shared with
+/// the other bitmap is the greater one.
 impl<const SIZE: usize> Ord for Bitmap<{ SIZE }>
 where
     BitsImpl<{ SIZE }>: Bits,
-    <BitsImpl<{ SIZE }> as Bits>::Store: Ord,
 {
     fn cmp(&self, other: &Self) -> core::cmp::Ordering {
-        self.as_value().cmp(other.as_value())
+        match (*self ^ *other).last_index() {
+            None => core::cmp::Ordering::Equal,
+            Some(index) if self.get(index) => core::cmp::Ordering::Greater,
+            Some(_) => core::cmp::Ordering::Less,
+        }
     }
 }
 
-#[cfg(feature = "std")]
-impl<const SIZE: usize> Debug for Bitmap<{ SIZE }>
+impl<const SIZE: usize> Bitmap<{ SIZE }>
 where
     BitsImpl<{ SIZE }>: Bits,
 {
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(
-            f,
-            "{}",
-            <BitsImpl::<SIZE> as Bits>::Store::to_hex(&self.data)
+    /// Write the digits of the bitmap as a number in a power of two radix,
+    /// with `digit_bits` bits per digit, padded with zeros to the size of
+    /// the bitmap, into `buffer`.
+    pub(crate) fn radix_digits<'a>(
+        &self,
+        buffer: &'a mut [u8; SIZE],
+        digit_bits: usize,
+        digits: &[u8; 16],
+    ) -> &'a str {
+        let len = SIZE.div_ceil(digit_bits);
+        for (position, out) in buffer[..len].iter_mut().rev().enumerate() {
+            let start = position * digit_bits;
+            let end = SIZE.min(start + digit_bits);
+            *out = digits[self.get_bits(start..end) as usize];
+        }
+        // The buffer only contains ASCII digits.
+        core::str::from_utf8(&buffer[..len]).unwrap()
+    }
+
+    /// Write the bitmap as a number in a power of two radix, with
+    /// `digit_bits` bits per digit, padded with zeros to the size of the
+    /// bitmap.
+    fn fmt_radix(
+        &self,
+        f: &mut Formatter<'_>,
+        digit_bits: usize,
+        prefix: &str,
+        digits: &[u8; 16],
+    ) -> core::fmt::Result {
+        let mut buffer = [0; SIZE];
+        f.pad_integral(
+            true,
+            prefix,
+            self.radix_digits(&mut buffer, digit_bits, digits),
         )
     }
 }
 
+pub(crate) const LOWER_DIGITS: &[u8; 16] = b"0123456789abcdef";
+const UPPER_DIGITS: &[u8; 16] = b"0123456789ABCDEF";
+
+/// Format the bitmap as a binary number, with the highest index first,
+/// padded with zeros to `SIZE` digits.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let bitmap = Bitmap::<6>::from_range(0..2);
+/// assert_eq!(format!("{:b}", bitmap), "000011");
+/// assert_eq!(format!("{:#b}", bitmap), "0b000011");
+/// ```
+impl<const SIZE: usize> Binary for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.fmt_radix(f, 1, "0b", LOWER_DIGITS)
+    }
+}
+
+/// Format the bitmap as a string of `SIZE` `0`s and `1`s, one for each bit,
+/// with the highest index first.
+///
+/// This is the same as the [`Binary`] format without the `0b` prefix, so
+/// the bit at index 0 is the last character.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let mut bitmap = Bitmap::<8>::new();
+/// bitmap.set(0, true);
+/// bitmap.set(6, true);
+/// assert_eq!(bitmap.to_string(), "01000001");
+/// ```
+impl<const SIZE: usize> Display for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.fmt_radix(f, 1, "", LOWER_DIGITS)
+    }
+}
+
+/// Format the bitmap as an octal number, with the highest index first,
+/// padded with zeros to enough digits to hold `SIZE` bits.
+impl<const SIZE: usize> Octal for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.fmt_radix(f, 3, "0o", LOWER_DIGITS)
+    }
+}
+
+/// Format the bitmap as a lower case hexadecimal number, with the highest
+/// index first, padded with zeros to enough digits to hold `SIZE` bits.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let bitmap = Bitmap::<10>::from_range(0..5);
+/// assert_eq!(format!("{:x}", bitmap), "01f");
+/// assert_eq!(format!("{:#X}", bitmap), "0x01F");
+/// ```
+impl<const SIZE: usize> LowerHex for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.fmt_radix(f, 4, "0x", LOWER_DIGITS)
+    }
+}
+
+/// Format the bitmap as an upper case hexadecimal number, with the highest
+/// index first, padded with zeros to enough digits to hold `SIZE` bits.
+impl<const SIZE: usize> UpperHex for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        self.fmt_radix(f, 4, "0x", UPPER_DIGITS)
+    }
+}
+
+/// Format the bitmap as its size followed by the set of indices of its
+/// `true` bits, eg. `Bitmap<64> {3, 5, 8}`.
+impl<const SIZE: usize> Debug for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Bitmap<{}> ", SIZE)?;
+        f.debug_set().entries(self).finish()
+    }
+}
+
+/// Parse a bitmap from a number in binary, either bare or prefixed with
+/// `0b`, or in hexadecimal, prefixed with `0x`, as produced by the
+/// [`Binary`] and [`LowerHex`] formats.
+///
+/// The highest index comes first. The string can have fewer digits than the
+/// bitmap has bits, but it's an error for it to set any bit past the end of
+/// the bitmap.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let bitmap: Bitmap<10> = "0b101".parse().unwrap();
+/// assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 2]);
+/// assert_eq!("0x3ff".parse::<Bitmap<10>>(), Ok(Bitmap::ones()));
+/// assert!("0x7ff".parse::<Bitmap<10>>().is_err());
+/// ```
+impl<const SIZE: usize> FromStr for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (digits, digit_bits) = if let Some(digits) = s.strip_prefix("0x") {
+            (digits, 4)
+        } else if let Some(digits) = s.strip_prefix("0b") {
+            (digits, 1)
+        } else {
+            (s, 1)
+        };
+        if digits.is_empty() {
+            return Err(Error::Empty);
+        }
+        let mut bitmap = Self::new();
+        for (position, digit) in digits.chars().rev().enumerate() {
+            let value = digit.to_digit(1 << digit_bits).ok_or(Error::InvalidDigit)? as u128;
+            let start = position * digit_bits;
+            if value >> SIZE.saturating_sub(start).min(digit_bits) != 0 {
+                return Err(Error::Overflow { size: SIZE });
+            }
+            if value != 0 {
+                bitmap.set_bits(start..start + digit_bits.min(SIZE - start), value);
+            }
+        }
+        Ok(bitmap)
+    }
+}
+
 impl<const SIZE: usize> AsRef<[u8]> for Bitmap<{ SIZE }>
 where
     BitsImpl<{ SIZE }>: Bits,
@@ -128,58 +331,93 @@ where
     }
 }
 
+/// Construct a bitmap from a slice of bytes in little endian order, as
+/// produced by [`Bitmap::to_le_bytes`].
+///
+/// The slice must be exactly as long as the bitmap's backing store, or
+/// [`Error::InvalidLength`] is returned, and any bits past the end of the
+/// bitmap must be zero, or [`Error::Overflow`] is returned.
 impl<const SIZE: usize> TryFrom<&[u8]> for Bitmap<{ SIZE }>
 where
     BitsImpl<{ SIZE }>: Bits,
 {
-    type Error = ();
+    type Error = Error;
 
     fn try_from(value: &[u8]) -> Result<Self, Self::Error> {
-        if value.len() == size_of::<<BitsImpl<SIZE> as Bits>::Store>() {
-            let mut data: MaybeUninit<<BitsImpl<SIZE> as Bits>::Store> = MaybeUninit::uninit();
-            let data_ptr: *mut u8 = data.as_mut_ptr().cast();
-            Ok(unsafe {
-                data_ptr.copy_from_nonoverlapping(value.as_ptr(), value.len());
-                Self {
-                    data: data.assume_init(),
-                }
-            })
+        let mut bytes = <BitsImpl<SIZE> as Bits>::Store::to_le_bytes(&Default::default());
+        if value.len() != bytes.as_ref().len() {
+            return Err(Error::InvalidLength {
+                expected: bytes.as_ref().len(),
+                found: value.len(),
+            });
+        }
+        bytes.as_mut().copy_from_slice(value);
+        let bitmap = Self::from_le_bytes(bytes);
+        if bitmap.to_le_bytes().as_ref() == value {
+            Ok(bitmap)
         } else {
-            Err(())
+            Err(Error::Overflow { size: SIZE })
         }
     }
 }
 
-#[cfg(not(feature = "std"))]
-impl<const SIZE: usize> Debug for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "Bitmap<{}> {{ ... }}", SIZE)
-    }
-}
-
 impl<const SIZE: usize> Bitmap<{ SIZE }>
 where
     BitsImpl<SIZE>: Bits,
 {
+    /// The number of bits in the bitmap.
+    pub const LENGTH: usize = SIZE;
+
+    /// The number of bytes written by [`Bitmap::encode`].
+    pub const ENCODED_LEN: usize = 5 + SIZE.div_ceil(8);
+
+    /// The current version of the format written by [`Bitmap::encode`].
+    const ENCODING_VERSION: u8 = 1;
+
     /// Construct a bitmap with every bit set to `false`.
     #[inline]
     pub fn new() -> Self {
         Self::default()
     }
 
+    /// Construct a bitmap with every bit set to `true`.
+    #[inline]
+    pub fn ones() -> Self {
+        Self::mask(SIZE)
+    }
+
     /// Construct a bitmap where every bit with index less than `bits` is
     /// `true`, and every other bit is `false`.
+    ///
+    /// Panics if `bits` is larger than `SIZE`.
     #[inline]
     pub fn mask(bits: usize) -> Self {
-        debug_assert!(bits <= SIZE);
+        assert!(
+            bits <= SIZE,
+            "mask: {} bits is larger than the bitmap size {}",
+            bits,
+            SIZE
+        );
         Self {
             data: <BitsImpl<SIZE> as Bits>::Store::make_mask(bits),
         }
     }
 
+    /// Construct a bitmap where every bit with an index inside `range` is
+    /// `true`, and every other bit is `false`.
+    ///
+    /// Panics if the end of `range` is larger than `SIZE`.
+    #[inline]
+    pub fn from_range(range: Range<usize>) -> Self {
+        assert!(
+            range.end <= SIZE,
+            "from_range: range end {} is larger than the bitmap size {}",
+            range.end,
+            SIZE
+        );
+        Self::mask(range.end) - Self::mask(range.start.min(range.end))
+    }
+
     /// Construct a bitmap from a value of the same type as its backing store.
     #[inline]
     pub fn from_value(data: <BitsImpl<SIZE> as Bits>::Store) -> Self {
@@ -198,608 +436,4118 @@ where
         &self.data
     }
 
+    /// Get a mutable reference to this bitmap's backing store.
+    ///
+    /// If the backing store has more bits than the bitmap, the bits above
+    /// index `SIZE - 1` must be left as `false`, or the results of other
+    /// methods on the bitmap will be incorrect.
     #[inline]
-    pub fn as_bytes(&self) -> &[u8] {
-        AsRef::<[u8]>::as_ref(self)
+    pub fn as_value_mut(&mut self) -> &mut <BitsImpl<SIZE> as Bits>::Store {
+        &mut self.data
     }
 
-    /// Count the number of `true` bits in the bitmap.
+    /// Get this bitmap's backing store as a slice of words.
+    ///
+    /// For bitmaps backed by an array of `u128`s, this is the array. For
+    /// bitmaps backed by a single primitive, this is a slice of length one
+    /// containing it. In either case, the word type converts into a `u128`,
+    /// and bit `n` of the bitmap is bit `n % W` of word `n / W`, where `W` is
+    /// the number of bits in a word.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<200>::from_range(120..136);
+    /// let words: Vec<u128> = bitmap.as_words().iter().map(|&word| word.into()).collect();
+    /// assert_eq!(words, vec![0xff << 120, 0xff]);
+    /// ```
     #[inline]
-    pub fn len(self) -> usize {
-        <BitsImpl<SIZE> as Bits>::Store::len(&self.data)
+    pub fn as_words(&self) -> &[<<BitsImpl<SIZE> as Bits>::Store as BitOps>::Word] {
+        <BitsImpl<SIZE> as Bits>::Store::as_words(&self.data)
     }
 
-    /// Test if the bitmap contains only `false` bits.
+    /// Get this bitmap's backing store as a mutable slice of words.
+    ///
+    /// The layout is the same as for [`Bitmap::as_words`]. As with
+    /// [`Bitmap::as_value_mut`], the bits above index `SIZE - 1` must be left
+    /// as `false`.
     #[inline]
-    pub fn is_empty(self) -> bool {
-        self.first_index().is_none()
+    pub fn as_words_mut(&mut self) -> &mut [<<BitsImpl<SIZE> as Bits>::Store as BitOps>::Word] {
+        <BitsImpl<SIZE> as Bits>::Store::as_words_mut(&mut self.data)
     }
 
-    /// Test if the bitmap contains only `true` bits.
+    /// Get the bytes of this bitmap's backing store.
+    ///
+    /// The bytes are laid out in the host's native byte order: the store's
+    /// words are in index order, and the bytes of each word are in native
+    /// endian order. On a little endian host, bit `n` of the bitmap is bit
+    /// `n % 8` of byte `n / 8`. Use [`Bitmap::to_le_bytes`] for a layout
+    /// which doesn't depend on the host.
     #[inline]
-    pub fn is_full(self) -> bool {
-        self.first_false_index().is_none()
+    pub fn as_bytes(&self) -> &[u8] {
+        AsRef::<[u8]>::as_ref(self)
     }
 
-    /// Get the value of the bit at a given index.
+    /// Get a raw pointer to the first byte of this bitmap's backing store.
+    ///
+    /// The pointer is valid for reads of `size_of::<Bitmap<SIZE>>()` bytes,
+    /// laid out as described for [`Bitmap::as_bytes`], for as long as the
+    /// bitmap is borrowed.
     #[inline]
-    pub fn get(self, index: usize) -> bool {
-        debug_assert!(index < SIZE);
-        <BitsImpl<SIZE> as Bits>::Store::get(&self.data, index)
+    pub fn as_ptr(&self) -> *const u8 {
+        &self.data as *const _ as *const u8
     }
 
-    /// Set the value of the bit at a given index.
+    /// Get a raw mutable pointer to the first byte of this bitmap's backing
+    /// store.
     ///
-    /// Returns the previous value of the bit.
+    /// The same requirements apply to writes through the pointer as to
+    /// [`Bitmap::as_bytes_mut`].
     #[inline]
-    pub fn set(&mut self, index: usize, value: bool) -> bool {
-        debug_assert!(index < SIZE);
-        <BitsImpl<SIZE> as Bits>::Store::set(&mut self.data, index, value)
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        &mut self.data as *mut _ as *mut u8
     }
 
-    /// Find the index of the first `true` bit in the bitmap.
+    /// Get the bytes of this bitmap's backing store as a mutable slice.
+    ///
+    /// The layout is the same as for [`Bitmap::as_bytes`]. As with
+    /// [`Bitmap::as_value_mut`], the bits above index `SIZE - 1` must be left
+    /// as `false`. For a `Bitmap<1>`, the single byte must be either `0` or
+    /// `1`, as the backing store is a `bool`.
     #[inline]
-    pub fn first_index(self) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::Store::first_index(&self.data)
+    pub fn as_bytes_mut(&mut self) -> &mut [u8] {
+        AsMut::<[u8]>::as_mut(self)
     }
 
-    /// Find the index of the last `true` bit in the bitmap.
+    /// Convert this bitmap into an array of bytes in little endian order.
+    ///
+    /// Bit `n` of the bitmap is bit `n % 8` of byte `n / 8`, regardless of
+    /// the host's byte order. The array has the same length as the bitmap's
+    /// backing store, so any bytes past the end of the bitmap are zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut bitmap = Bitmap::<12>::new();
+    /// bitmap.set(0, true);
+    /// bitmap.set(9, true);
+    /// assert_eq!(bitmap.to_le_bytes(), [0b1, 0b10]);
+    /// assert_eq!(bitmap.to_be_bytes(), [0b10, 0b1]);
+    /// ```
     #[inline]
-    pub fn last_index(self) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::Store::last_index(&self.data)
+    pub fn to_le_bytes(&self) -> <<BitsImpl<SIZE> as Bits>::Store as BitOps>::Bytes {
+        <BitsImpl<SIZE> as Bits>::Store::to_le_bytes(&self.data)
     }
 
-    /// Find the index of the first `true` bit in the bitmap after `index`.
+    /// Convert this bitmap into an array of bytes in big endian order.
+    ///
+    /// This is the reverse of [`Bitmap::to_le_bytes`]: bit `n` of the
+    /// bitmap is bit `n % 8` of the `n / 8`th byte from the end.
     #[inline]
-    pub fn next_index(self, index: usize) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::Store::next_index(&self.data, index)
+    pub fn to_be_bytes(&self) -> <<BitsImpl<SIZE> as Bits>::Store as BitOps>::Bytes {
+        <BitsImpl<SIZE> as Bits>::Store::to_be_bytes(&self.data)
     }
 
-    /// Find the index of the last `true` bit in the bitmap before `index`.
+    /// Construct a bitmap from an array of bytes in little endian order, as
+    /// produced by [`Bitmap::to_le_bytes`].
+    ///
+    /// Any bits past the end of the bitmap are ignored.
     #[inline]
-    pub fn prev_index(self, index: usize) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::Store::prev_index(&self.data, index)
+    pub fn from_le_bytes(bytes: <<BitsImpl<SIZE> as Bits>::Store as BitOps>::Bytes) -> Self {
+        Self::from_value(<BitsImpl<SIZE> as Bits>::Store::from_le_bytes(bytes)) & Self::ones()
     }
 
-    /// Find the index of the first `false` bit in the bitmap.
+    /// Construct a bitmap from an array of bytes in big endian order, as
+    /// produced by [`Bitmap::to_be_bytes`].
+    ///
+    /// Any bits past the end of the bitmap are ignored.
     #[inline]
-    pub fn first_false_index(self) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::corrected_first_false_index(&self.data)
+    pub fn from_be_bytes(bytes: <<BitsImpl<SIZE> as Bits>::Store as BitOps>::Bytes) -> Self {
+        Self::from_value(<BitsImpl<SIZE> as Bits>::Store::from_be_bytes(bytes)) & Self::ones()
     }
 
-    /// Find the index of the last `false` bit in the bitmap.
+    /// Append the bitmap to `out` in a stable binary format.
+    ///
+    /// The format doesn't depend on the bitmap's backing store or on the
+    /// platform, and won't change between versions of this crate. It's
+    /// always [`Bitmap::ENCODED_LEN`] bytes long, laid out as:
+    ///
+    /// * one byte for the format version, currently `1`;
+    /// * four bytes for the size of the bitmap, as a little endian `u32`;
+    /// * `SIZE / 8` bytes, rounded up, for the bits: bit `n` of the bitmap
+    ///   is bit `n % 8` of the `n / 8`th byte. Any bits past the end of the
+    ///   bitmap are zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<12>::from_range(8..10);
+    /// let mut out = Vec::new();
+    /// bitmap.encode(&mut out);
+    /// assert_eq!(out, [1, 12, 0, 0, 0, 0, 0b11]);
+    /// assert_eq!(Bitmap::decode(&out), Ok(bitmap));
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn encode(&self, out: &mut Vec<u8>) {
+        out.push(Self::ENCODING_VERSION);
+        out.extend_from_slice(&(SIZE as u32).to_le_bytes());
+        out.extend_from_slice(&self.to_le_bytes().as_ref()[..SIZE.div_ceil(8)]);
+    }
+
+    /// Read a bitmap written by [`Bitmap::encode`].
+    ///
+    /// The slice must be exactly [`Bitmap::ENCODED_LEN`] bytes long, and
+    /// must have been encoded from a bitmap of the same size.
+    pub fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid_length = Error::InvalidLength {
+            expected: Self::ENCODED_LEN,
+            found: bytes.len(),
+        };
+        let (&version, rest) = bytes.split_first().ok_or(invalid_length)?;
+        if version != Self::ENCODING_VERSION {
+            return Err(Error::UnsupportedVersion { version });
+        }
+        let (size, body) = rest.split_at_checked(4).ok_or(invalid_length)?;
+        let size = u32::from_le_bytes(size.try_into().unwrap()) as usize;
+        if size != SIZE {
+            return Err(Error::SizeMismatch {
+                expected: SIZE,
+                found: size,
+            });
+        }
+        if bytes.len() != Self::ENCODED_LEN {
+            return Err(invalid_length);
+        }
+        Self::from_packed_le_bytes(body)
+    }
+
+    /// Read a bitmap from exactly `SIZE.div_ceil(8)` bytes in little endian
+    /// order, with no padding up to the size of the backing store.
+    ///
+    /// Any bits past the end of the bitmap must be zero.
+    pub(crate) fn from_packed_le_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != SIZE.div_ceil(8) {
+            return Err(Error::InvalidLength {
+                expected: SIZE.div_ceil(8),
+                found: bytes.len(),
+            });
+        }
+        let mut store = <BitsImpl<SIZE> as Bits>::Store::to_le_bytes(&Default::default());
+        store.as_mut()[..bytes.len()].copy_from_slice(bytes);
+        let bitmap = Self::from_le_bytes(store);
+        if bitmap.to_le_bytes().as_ref() == store.as_ref() {
+            Ok(bitmap)
+        } else {
+            Err(Error::Overflow { size: SIZE })
+        }
+    }
+
+    /// Get the number of bits in the bitmap, regardless of their values.
+    ///
+    /// This is the same as [`Bitmap::LENGTH`].
     #[inline]
-    pub fn last_false_index(self) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::corrected_last_false_index(&self.data)
+    pub const fn capacity(self) -> usize {
+        SIZE
     }
 
-    /// Find the index of the first `false` bit in the bitmap after `index`.
+    /// Count the number of `true` bits in the bitmap.
     #[inline]
-    pub fn next_false_index(self, index: usize) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::corrected_next_false_index(&self.data, index)
+    pub fn len(self) -> usize {
+        <BitsImpl<SIZE> as Bits>::Store::len(&self.data)
     }
 
-    /// Find the index of the first `false` bit in the bitmap before `index`.
+    /// Count the number of `false` bits in the bitmap.
     #[inline]
-    pub fn prev_false_index(self, index: usize) -> Option<usize> {
-        <BitsImpl<SIZE> as Bits>::Store::prev_false_index(&self.data, index)
+    pub fn count_zeros(self) -> usize {
+        SIZE - self.len()
     }
 
-    /// Invert all the bits in the bitmap.
+    /// Count the number of `true` bits with an index inside `range`.
     #[inline]
-    pub fn invert(&mut self) {
-        <BitsImpl<SIZE> as Bits>::Store::invert(&mut self.data);
+    pub fn count_in_range(self, range: impl RangeBounds<usize>) -> usize {
+        (self & Self::from_range(Self::resolve_range(range))).len()
     }
-}
 
-impl<'a, const SIZE: usize> IntoIterator for &'a Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    type Item = usize;
-    type IntoIter = Iter<'a, { SIZE }>;
+    /// Get an iterator over the number of `true` bits in each window of
+    /// `width` consecutive bits, sliding along the bitmap one bit at a time.
+    ///
+    /// The first count is for the bits `0..width`, and the last is for the
+    /// bits `SIZE - width..SIZE`. Each count after the first is computed from
+    /// the previous one. If `width` is larger than the bitmap, there are no
+    /// windows.
+    ///
+    /// Panics if `width` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<6>::from_value(0b110101);
+    /// assert_eq!(bitmap.window_counts(3).collect::<Vec<_>>(), vec![2, 1, 2, 2]);
+    /// ```
+    pub fn window_counts(
+        self,
+        width: usize,
+    ) -> impl ExactSizeIterator<Item = usize> + FusedIterator {
+        assert!(width > 0, "window_counts: width must be greater than zero");
+        let windows = (SIZE + 1).saturating_sub(width);
+        let mut count = if windows > 0 {
+            self.count_in_range(..width)
+        } else {
+            0
+        };
+        (0..windows).map(move |start| {
+            if start > 0 {
+                count -= self.get(start - 1) as usize;
+                count += self.get(start + width - 1) as usize;
+            }
+            count
+        })
+    }
 
-    fn into_iter(self) -> Self::IntoIter {
-        Iter {
-            head: None,
-            tail: Some(SIZE + 1),
-            data: self,
-        }
+    /// Count the number of `true` bits with an index lower than `index`.
+    #[inline]
+    pub fn rank(self, index: usize) -> usize {
+        self.count_in_range(..index)
     }
-}
 
-impl<const SIZE: usize> BitAnd for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    type Output = Self;
-    fn bitand(mut self, rhs: Self) -> Self::Output {
-        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
-        self
+    /// Test if the bitmap contains an odd number of `true` bits, ie. the
+    /// XOR of all of its bits.
+    #[inline]
+    pub fn parity(self) -> bool {
+        self.len() % 2 == 1
     }
-}
 
-impl<const SIZE: usize> BitOr for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    type Output = Self;
-    fn bitor(mut self, rhs: Self) -> Self::Output {
-        <BitsImpl<SIZE> as Bits>::Store::bit_or(&mut self.data, &rhs.data);
-        self
+    /// Test if the bitmap contains only `false` bits.
+    #[inline]
+    pub fn is_empty(self) -> bool {
+        self.first_index().is_none()
     }
-}
 
-impl<const SIZE: usize> BitXor for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    type Output = Self;
-    fn bitxor(mut self, rhs: Self) -> Self::Output {
-        <BitsImpl<SIZE> as Bits>::Store::bit_xor(&mut self.data, &rhs.data);
-        self
+    /// Test if the bitmap contains only `true` bits.
+    #[inline]
+    pub fn is_full(self) -> bool {
+        self.first_false_index().is_none()
     }
-}
 
-impl<const SIZE: usize> Not for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    type Output = Self;
-    fn not(mut self) -> Self::Output {
-        <BitsImpl<SIZE> as Bits>::Store::invert(&mut self.data);
-        self
+    /// Test if every bit with an index inside `range` is `true`.
+    #[inline]
+    pub fn all_in_range(self, range: impl RangeBounds<usize>) -> bool {
+        let mask = Self::from_range(Self::resolve_range(range));
+        self & mask == mask
     }
-}
 
-impl<const SIZE: usize> BitAndAssign for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    fn bitand_assign(&mut self, rhs: Self) {
-        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
+    /// Test if any bit with an index inside `range` is `true`.
+    #[inline]
+    pub fn any_in_range(self, range: impl RangeBounds<usize>) -> bool {
+        !(self & Self::from_range(Self::resolve_range(range))).is_empty()
     }
-}
 
-impl<const SIZE: usize> BitOrAssign for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    fn bitor_assign(&mut self, rhs: Self) {
-        <BitsImpl<SIZE> as Bits>::Store::bit_or(&mut self.data, &rhs.data);
+    /// Test if the bitmap contains exactly one `true` bit.
+    #[inline]
+    pub fn is_single_bit(self) -> bool {
+        match self.first_index() {
+            Some(index) => self.next_index(index).is_none(),
+            None => false,
+        }
     }
-}
 
-impl<const SIZE: usize> BitXorAssign for Bitmap<{ SIZE }>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    fn bitxor_assign(&mut self, rhs: Self) {
-        <BitsImpl<SIZE> as Bits>::Store::bit_xor(&mut self.data, &rhs.data);
+    /// Get the value of the bit at a given index.
+    #[inline]
+    pub fn get(self, index: usize) -> bool {
+        debug_assert!(index < SIZE);
+        <BitsImpl<SIZE> as Bits>::Store::get(&self.data, index)
     }
-}
 
-impl From<[u128; 2]> for Bitmap<256> {
-    fn from(data: [u128; 2]) -> Self {
-        Bitmap { data }
+    /// Get the value of the bit at a given index, without bounds checking.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `SIZE`. For the bitmaps backed by arrays,
+    /// an out of bounds index is undefined behaviour.
+    #[inline]
+    pub unsafe fn get_unchecked(self, index: usize) -> bool {
+        debug_assert!(index < SIZE);
+        unsafe { <BitsImpl<SIZE> as Bits>::Store::get_unchecked(&self.data, index) }
     }
-}
 
-impl From<[u128; 3]> for Bitmap<384> {
-    fn from(data: [u128; 3]) -> Self {
-        Bitmap { data }
+    /// Get the bits with indices inside `range` as an integer, with the
+    /// first bit of the range as its least significant bit.
+    ///
+    /// The range can't be more than 128 bits long.
+    #[inline]
+    pub fn get_bits(self, range: impl RangeBounds<usize>) -> u128 {
+        let range = Self::resolve_range(range);
+        if range.start >= range.end {
+            return 0;
+        }
+        debug_assert!(range.end <= SIZE);
+        assert!(range.len() <= 128, "get_bits: range is wider than 128 bits");
+        <BitsImpl<SIZE> as Bits>::Store::get_bits(&self.data, range.start, range.len())
     }
-}
 
-impl From<[u128; 4]> for Bitmap<512> {
-    fn from(data: [u128; 4]) -> Self {
-        Bitmap { data }
+    /// Set the bits with indices inside `range` from the bits of an
+    /// integer, with the first bit of the range taken from its least
+    /// significant bit.
+    ///
+    /// The range can't be more than 128 bits long. Bits of `value` which don't
+    /// fit in the range are ignored.
+    #[inline]
+    pub fn set_bits(&mut self, range: impl RangeBounds<usize>, value: u128) {
+        let range = Self::resolve_range(range);
+        if range.start >= range.end {
+            return;
+        }
+        debug_assert!(range.end <= SIZE);
+        assert!(range.len() <= 128, "set_bits: range is wider than 128 bits");
+        <BitsImpl<SIZE> as Bits>::Store::set_bits(&mut self.data, range.start, range.len(), value)
     }
-}
 
-impl From<[u128; 5]> for Bitmap<640> {
-    fn from(data: [u128; 5]) -> Self {
-        Bitmap { data }
+    /// Set the value of the bit at a given index.
+    ///
+    /// Returns the previous value of the bit.
+    #[inline]
+    pub fn set(&mut self, index: usize, value: bool) -> bool {
+        debug_assert!(index < SIZE);
+        <BitsImpl<SIZE> as Bits>::Store::set(&mut self.data, index, value)
     }
-}
 
-impl From<[u128; 6]> for Bitmap<768> {
-    fn from(data: [u128; 6]) -> Self {
-        Bitmap { data }
+    /// Set the value of the bit at a given index, without bounds checking.
+    ///
+    /// Returns the previous value of the bit.
+    ///
+    /// # Safety
+    ///
+    /// `index` must be less than `SIZE`. For the bitmaps backed by arrays,
+    /// an out of bounds index is undefined behaviour.
+    #[inline]
+    pub unsafe fn set_unchecked(&mut self, index: usize, value: bool) -> bool {
+        debug_assert!(index < SIZE);
+        unsafe { <BitsImpl<SIZE> as Bits>::Store::set_unchecked(&mut self.data, index, value) }
     }
-}
 
-impl From<[u128; 7]> for Bitmap<896> {
-    fn from(data: [u128; 7]) -> Self {
-        Bitmap { data }
+    /// Get the value of the bit at a given index, or `None` if the index is
+    /// out of bounds.
+    #[inline]
+    pub fn try_get(self, index: usize) -> Option<bool> {
+        if index < SIZE {
+            Some(self.get(index))
+        } else {
+            None
+        }
     }
-}
 
-impl From<[u128; 8]> for Bitmap<1024> {
-    fn from(data: [u128; 8]) -> Self {
-        Bitmap { data }
+    /// Set the value of the bit at a given index, or return an error if the
+    /// index is out of bounds.
+    ///
+    /// Returns the previous value of the bit.
+    #[inline]
+    pub fn try_set(&mut self, index: usize, value: bool) -> Result<bool, Error> {
+        if index < SIZE {
+            Ok(self.set(index, value))
+        } else {
+            Err(Error::OutOfBounds { index, size: SIZE })
+        }
     }
-}
 
-impl From<Bitmap<256>> for [u128; 2] {
-    fn from(bitmap: Bitmap<256>) -> Self {
-        bitmap.into_value()
+    /// Flip the value of the bit at a given index.
+    ///
+    /// Returns the previous value of the bit.
+    #[inline]
+    pub fn toggle(&mut self, index: usize) -> bool {
+        debug_assert!(index < SIZE);
+        <BitsImpl<SIZE> as Bits>::Store::toggle(&mut self.data, index)
     }
-}
 
-impl From<Bitmap<384>> for [u128; 3] {
-    fn from(bitmap: Bitmap<384>) -> Self {
-        bitmap.into_value()
+    /// Get a mutable reference to the bit at a given index.
+    ///
+    /// Panics if the index is out of bounds.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut bitmap = Bitmap::<10>::new();
+    /// let mut bit = bitmap.bit_mut(3);
+    /// assert!(!bit.replace(true));
+    /// assert!(bit.toggle());
+    /// bit.toggle();
+    /// assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![3]);
+    /// ```
+    #[inline]
+    pub fn bit_mut(&mut self, index: usize) -> BitRef<'_, SIZE> {
+        assert!(
+            index < SIZE,
+            "index {} is out of bounds for a bitmap of size {}",
+            index,
+            SIZE
+        );
+        BitRef {
+            bitmap: self,
+            index,
+        }
     }
-}
 
-impl From<Bitmap<512>> for [u128; 4] {
-    fn from(bitmap: Bitmap<512>) -> Self {
-        bitmap.into_value()
+    /// Set every bit in the bitmap to `false`.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.data = <BitsImpl<SIZE> as Bits>::Store::default();
     }
-}
 
-impl From<Bitmap<640>> for [u128; 5] {
-    fn from(bitmap: Bitmap<640>) -> Self {
-        bitmap.into_value()
+    /// Set every bit in the bitmap to `value`.
+    #[inline]
+    pub fn fill(&mut self, value: bool) {
+        *self = if value { Self::ones() } else { Self::new() };
     }
-}
 
-impl From<Bitmap<768>> for [u128; 6] {
-    fn from(bitmap: Bitmap<768>) -> Self {
-        bitmap.into_value()
+    /// Set the value of every bit with an index inside `range`.
+    #[inline]
+    pub fn set_range(&mut self, range: impl RangeBounds<usize>, value: bool) {
+        let mask = Self::from_range(Self::resolve_range(range));
+        if value {
+            *self |= &mask;
+        } else {
+            *self -= mask;
+        }
     }
-}
 
-impl From<Bitmap<896>> for [u128; 7] {
-    fn from(bitmap: Bitmap<896>) -> Self {
-        bitmap.into_value()
+    /// Invert every bit with an index inside `range`.
+    #[inline]
+    pub fn invert_range(&mut self, range: impl RangeBounds<usize>) {
+        *self ^= &Self::from_range(Self::resolve_range(range));
     }
-}
 
-impl From<Bitmap<1024>> for [u128; 8] {
-    fn from(bitmap: Bitmap<1024>) -> Self {
-        bitmap.into_value()
+    /// Set every bit whose index is in `indices` to `true`.
+    ///
+    /// The indices are gathered into a mask first, so each word of the
+    /// bitmap is only written once.
+    pub fn set_many(&mut self, indices: &[usize]) {
+        *self |= &Self::mask_of(indices);
     }
-}
-
-/// An iterator over the indices in a bitmap which are `true`.
-///
-/// This yields a sequence of `usize` indices, not their contents (which are
-/// always `true` anyway, by definition).
-///
-/// # Examples
-///
-/// ```rust
-/// # use bitmaps::Bitmap;
-/// let mut bitmap: Bitmap<10> = Bitmap::new();
-/// bitmap.set(3, true);
-/// bitmap.set(5, true);
-/// bitmap.set(8, true);
-/// let true_indices: Vec<usize> = bitmap.into_iter().collect();
-/// assert_eq!(vec![3, 5, 8], true_indices);
-/// ```
-#[derive(Clone, Debug)]
-pub struct Iter<'a, const SIZE: usize>
-where
-    BitsImpl<SIZE>: Bits,
-{
-    head: Option<usize>,
-    tail: Option<usize>,
-    data: &'a Bitmap<{ SIZE }>,
-}
 
-impl<'a, const SIZE: usize> Iterator for Iter<'a, SIZE>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    type Item = usize;
+    /// Set every bit whose index is in `indices` to `false`.
+    ///
+    /// The indices are gathered into a mask first, so each word of the
+    /// bitmap is only written once.
+    pub fn clear_many(&mut self, indices: &[usize]) {
+        *self -= Self::mask_of(indices);
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let result;
+    fn mask_of(indices: &[usize]) -> Self {
+        let mut mask = Self::new();
+        for index in indices {
+            mask.set(*index, true);
+        }
+        mask
+    }
 
-        match self.head {
-            None => {
-                result = self.data.first_index();
-            }
-            Some(index) => {
-                if index >= SIZE {
-                    result = None
-                } else {
-                    result = self.data.next_index(index);
-                }
+    /// Set every `true` bit in the bitmap to `false` unless the predicate
+    /// returns `true` for its index.
+    pub fn retain(&mut self, mut f: impl FnMut(usize) -> bool) {
+        let mut next = self.first_index();
+        while let Some(index) = next {
+            if !f(index) {
+                self.set(index, false);
             }
+            next = self.next_index(index);
         }
+    }
 
-        if let Some(index) = result {
-            if let Some(tail) = self.tail {
-                if tail < index {
-                    self.head = Some(SIZE + 1);
-                    self.tail = None;
-                    return None;
-                }
-            } else {
-                // tail is already done
-                self.head = Some(SIZE + 1);
-                return None;
+    /// Get an iterator over the words of the bitmap's backing store, as
+    /// laid out by [`Bitmap::as_words`], yielding each word's index along
+    /// with its value as a `u128`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<256>::from_range(127..129);
+    /// assert_eq!(
+    ///     bitmap.iter_words().collect::<Vec<_>>(),
+    ///     vec![(0, 1 << 127), (1, 1)]
+    /// );
+    /// assert_eq!(Bitmap::<8>::ones().iter_words().collect::<Vec<_>>(), vec![(0, 0xff)]);
+    /// ```
+    #[inline]
+    pub fn iter_words(
+        self,
+    ) -> impl DoubleEndedIterator<Item = (usize, u128)> + ExactSizeIterator + FusedIterator {
+        (0..self.as_words().len()).map(move |index| (index, self.as_words()[index].into()))
+    }
+
+    /// Split the bitmap into consecutive smaller bitmaps of `N` bits each.
+    ///
+    /// If `N` doesn't divide the size of the bitmap evenly, the last chunk
+    /// is padded with `false` bits.
+    ///
+    /// Panics if `N` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<20>::from_range(6..10);
+    /// let chunks: Vec<Bitmap<8>> = bitmap.chunks::<8>().collect();
+    /// assert_eq!(chunks, vec![
+    ///     Bitmap::from_value(0b1100_0000),
+    ///     Bitmap::from_value(0b0000_0011),
+    ///     Bitmap::new(),
+    /// ]);
+    /// ```
+    pub fn chunks<const N: usize>(
+        self,
+    ) -> impl DoubleEndedIterator<Item = Bitmap<N>> + ExactSizeIterator + FusedIterator
+    where
+        BitsImpl<N>: Bits,
+    {
+        assert!(N > 0, "chunks: chunk size must be greater than zero");
+        (0..SIZE.div_ceil(N)).map(move |chunk| {
+            let start = chunk * N;
+            let len = N.min(SIZE - start);
+            let mut out = Bitmap::<N>::new();
+            for offset in (0..len).step_by(128) {
+                let end = len.min(offset + 128);
+                out.set_bits(offset..end, self.get_bits(start + offset..start + end));
             }
+            out
+        })
+    }
 
-            self.head = Some(index);
-        } else {
-            self.head = Some(SIZE + 1);
+    /// Get an iterator over every bit in the bitmap, yielding each index
+    /// along with the value of the bit at that index.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<3>::from_range(1..2);
+    /// assert_eq!(
+    ///     bitmap.bits().collect::<Vec<_>>(),
+    ///     vec![(0, false), (1, true), (2, false)]
+    /// );
+    /// ```
+    #[inline]
+    pub fn bits(
+        self,
+    ) -> impl DoubleEndedIterator<Item = (usize, bool)> + ExactSizeIterator + FusedIterator {
+        (0..SIZE).map(move |index| (index, self.get(index)))
+    }
+
+    /// Get an iterator over the indices of the `true` bits in the bitmap,
+    /// starting at index `start`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<300>::from_range(100..103);
+    /// assert_eq!(bitmap.iter_from(101).collect::<Vec<_>>(), vec![101, 102]);
+    /// ```
+    #[inline]
+    pub fn iter_from(&self, start: usize) -> Iter<'_, SIZE> {
+        self.iter_range(start..)
+    }
+
+    /// Get an iterator over the indices of the `true` bits in the bitmap
+    /// which are inside `range`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<300>::from_range(100..200);
+    /// assert_eq!(bitmap.iter_range(50..=102).collect::<Vec<_>>(), vec![100, 101, 102]);
+    /// ```
+    #[inline]
+    pub fn iter_range(&self, range: impl RangeBounds<usize>) -> Iter<'_, SIZE> {
+        let range = Self::resolve_range(range);
+        Iter::new(*self & Self::from_range(range.start.min(SIZE)..range.end.min(SIZE)))
+    }
+
+    /// Get an iterator over the indices of the `false` bits in the bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<6>::from_range(1..4);
+    /// assert_eq!(bitmap.iter_zeros().collect::<Vec<_>>(), vec![0, 4, 5]);
+    /// ```
+    #[inline]
+    pub fn iter_zeros(&self) -> Iter<'_, SIZE> {
+        Iter::new(self.inverted())
+    }
+
+    /// Get an iterator over the indices of the `true` bits in the bitmap,
+    /// which clears each bit as it's yielded.
+    ///
+    /// When the iterator is dropped, any bits it hasn't yielded yet are
+    /// cleared too, so the bitmap is always empty afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut ready = Bitmap::<64>::from_range(3..6);
+    /// assert_eq!(ready.drain().next(), Some(3));
+    /// assert!(ready.is_empty());
+    /// ```
+    #[inline]
+    pub fn drain(&mut self) -> Drain<'_, SIZE> {
+        Drain { data: self }
+    }
+
+    /// Get an iterator over every subset of the `true` bits in the bitmap,
+    /// as bitmaps.
+    ///
+    /// The subsets are yielded in ascending numeric order, starting with the
+    /// empty bitmap and ending with `self`, so a bitmap with `n` `true` bits
+    /// yields `2^n` subsets.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<8>::from_value(0b1010);
+    /// let subsets: Vec<u8> = bitmap.subsets().map(|subset| subset.into_value()).collect();
+    /// assert_eq!(subsets, vec![0b0000, 0b0010, 0b1000, 0b1010]);
+    /// ```
+    #[inline]
+    pub fn subsets(self) -> Subsets<SIZE> {
+        Subsets {
+            mask: self,
+            next: Some(Self::new()),
         }
+    }
 
-        result
+    /// Get an iterator over every bitmap with the same number of `true` bits
+    /// as this one, starting with `self`.
+    ///
+    /// The bitmaps are yielded in ascending numeric order, using a
+    /// generalisation of Gosper's hack, so starting from
+    /// `Bitmap::mask(k)` yields every way of choosing `k` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<4>::mask(2);
+    /// let combinations: Vec<u8> = bitmap.combinations().map(|c| c.into_value()).collect();
+    /// assert_eq!(combinations, vec![0b0011, 0b0101, 0b0110, 0b1001, 0b1010, 0b1100]);
+    /// ```
+    #[inline]
+    pub fn combinations(self) -> Combinations<SIZE> {
+        Combinations { next: Some(self) }
     }
-}
 
-impl<'a, const SIZE: usize> DoubleEndedIterator for Iter<'a, SIZE>
-where
-    BitsImpl<{ SIZE }>: Bits,
-{
-    fn next_back(&mut self) -> Option<Self::Item> {
-        let result;
+    /// Get a cursor over the bitmap, positioned before its first bit.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<10>::from_range(2..5);
+    /// let mut cursor = bitmap.cursor();
+    /// assert_eq!(cursor.next_one(), Some(2));
+    /// assert_eq!(cursor.next_zero(), Some(5));
+    /// assert_eq!(cursor.prev_one(), Some(4));
+    /// assert_eq!(cursor.position(), 4);
+    /// assert_eq!(cursor.peek(), Some(true));
+    /// ```
+    #[inline]
+    pub fn cursor(&self) -> Cursor<'_, SIZE> {
+        Cursor {
+            data: self,
+            position: 0,
+        }
+    }
 
-        match self.tail {
-            None => {
-                result = None;
-            }
-            Some(index) => {
-                if index >= SIZE {
-                    result = self.data.last_index();
-                } else {
-                    result = self.data.prev_index(index);
-                }
+    /// Get an iterator over the maximal runs of `true` bits in the bitmap,
+    /// as ranges of indices.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut bitmap = Bitmap::<10>::from_range(1..4);
+    /// bitmap.set(6, true);
+    /// assert_eq!(bitmap.ranges().collect::<Vec<_>>(), vec![1..4, 6..7]);
+    /// assert_eq!(bitmap.zero_ranges().collect::<Vec<_>>(), vec![0..1, 4..6, 7..10]);
+    /// ```
+    #[inline]
+    pub fn ranges(self) -> Ranges<SIZE> {
+        Ranges { data: self }
+    }
+
+    /// Get an iterator over the maximal runs of `false` bits in the bitmap,
+    /// as ranges of indices.
+    #[inline]
+    pub fn zero_ranges(self) -> Ranges<SIZE> {
+        Ranges {
+            data: self.inverted(),
+        }
+    }
+
+    /// Call a function with the index of every `true` bit in the bitmap, in
+    /// ascending order.
+    ///
+    /// This does the same as iterating over the bitmap, but skips over
+    /// `false` bits a word at a time, which is considerably faster for
+    /// sparse bitmaps.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut bitmap = Bitmap::<1024>::new();
+    /// bitmap.set(3, true);
+    /// bitmap.set(700, true);
+    /// let mut indices = Vec::new();
+    /// bitmap.for_each(|index| indices.push(index));
+    /// assert_eq!(indices, vec![3, 700]);
+    /// ```
+    #[inline]
+    pub fn for_each(self, f: impl FnMut(usize)) {
+        <BitsImpl<SIZE> as Bits>::Store::for_each(&self.data, f)
+    }
+
+    /// Sum the weights at the indices of every `true` bit in the bitmap.
+    ///
+    /// This is the dot product of the bitmap with `weights`, and skips over
+    /// `false` bits a word at a time like [`Bitmap::for_each`].
+    ///
+    /// Panics if `weights` is shorter than the bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let weights = [1.5, 2.0, 4.0, 8.0];
+    /// let bitmap = Bitmap::<4>::from_range(1..3);
+    /// assert_eq!(bitmap.weighted_sum(&weights), 6.0);
+    /// ```
+    pub fn weighted_sum<W>(self, weights: &[W]) -> W
+    where
+        W: Copy + Default + Add<Output = W>,
+    {
+        assert!(
+            weights.len() >= SIZE,
+            "weights has {} elements but the bitmap has {} bits",
+            weights.len(),
+            SIZE
+        );
+        let mut sum = W::default();
+        self.for_each(|index| sum = sum + weights[index]);
+        sum
+    }
+
+    /// Find the index of the first `true` bit in the bitmap.
+    #[inline]
+    pub fn first_index(self) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::Store::first_index(&self.data)
+    }
+
+    /// Find the index of the last `true` bit in the bitmap.
+    #[inline]
+    pub fn last_index(self) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::Store::last_index(&self.data)
+    }
+
+    /// Find the index of the first `true` bit in the bitmap after `index`.
+    ///
+    /// Returns `None` if there is no such bit, including when `index` is
+    /// past the end of the bitmap.
+    #[inline]
+    pub fn next_index(self, index: usize) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::Store::next_index(&self.data, index)
+    }
+
+    /// Find the index of the first `true` bit in the bitmap after `after`,
+    /// wrapping around to the start of the bitmap if there is none.
+    ///
+    /// If `after` is the only `true` bit, it is returned itself. Returns
+    /// `None` only if the bitmap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut ready = Bitmap::<8>::new();
+    /// ready.set(2, true);
+    /// ready.set(5, true);
+    /// assert_eq!(ready.next_index_wrapping(2), Some(5));
+    /// assert_eq!(ready.next_index_wrapping(5), Some(2));
+    /// ```
+    #[inline]
+    pub fn next_index_wrapping(self, after: usize) -> Option<usize> {
+        self.next_index(after).or_else(|| self.first_index())
+    }
+
+    /// Find the index of the last `true` bit in the bitmap before `index`.
+    ///
+    /// If `index` is past the end of the bitmap, this is the same as
+    /// [`last_index()`][Bitmap::last_index].
+    #[inline]
+    pub fn prev_index(self, index: usize) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::Store::prev_index(&self.data, index)
+    }
+
+    /// Find the index of the first `false` bit in the bitmap.
+    #[inline]
+    pub fn first_false_index(self) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::corrected_first_false_index(&self.data)
+    }
+
+    /// Find the index of the last `false` bit in the bitmap.
+    #[inline]
+    pub fn last_false_index(self) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::corrected_last_false_index(&self.data)
+    }
+
+    /// Find the index of the first `false` bit in the bitmap after `index`.
+    #[inline]
+    pub fn next_false_index(self, index: usize) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::corrected_next_false_index(&self.data, index)
+    }
+
+    /// Find the index of the first `false` bit in the bitmap before `index`.
+    #[inline]
+    pub fn prev_false_index(self, index: usize) -> Option<usize> {
+        <BitsImpl<SIZE> as Bits>::corrected_prev_false_index(&self.data, index)
+    }
+
+    /// Count the number of `false` bits at the start of the bitmap, before
+    /// the first `true` bit.
+    #[inline]
+    pub fn trailing_zeros(self) -> usize {
+        self.first_index().unwrap_or(SIZE)
+    }
+
+    /// Count the number of `true` bits at the start of the bitmap, before
+    /// the first `false` bit.
+    #[inline]
+    pub fn trailing_ones(self) -> usize {
+        self.first_false_index().unwrap_or(SIZE)
+    }
+
+    /// Count the number of `false` bits at the end of the bitmap, after the
+    /// last `true` bit.
+    #[inline]
+    pub fn leading_zeros(self) -> usize {
+        self.last_index().map_or(SIZE, |index| SIZE - 1 - index)
+    }
+
+    /// Count the number of `true` bits at the end of the bitmap, after the
+    /// last `false` bit.
+    #[inline]
+    pub fn leading_ones(self) -> usize {
+        self.last_false_index()
+            .map_or(SIZE, |index| SIZE - 1 - index)
+    }
+
+    /// Find the longest contiguous run of `true` bits in the bitmap.
+    ///
+    /// Returns the index of the first bit in the run and the length of the
+    /// run. If there are several runs of the same length, the first one is
+    /// returned.
+    pub fn longest_run_of_ones(self) -> Option<(usize, usize)> {
+        let mut longest: Option<(usize, usize)> = None;
+        let mut next = self.first_index();
+        while let Some(start) = next {
+            let end = self.next_false_index(start).unwrap_or(SIZE);
+            if longest.map_or(true, |(_, len)| end - start > len) {
+                longest = Some((start, end - start));
+            }
+            next = self.next_index(end);
+        }
+        longest
+    }
+
+    /// Find the longest contiguous run of `false` bits in the bitmap.
+    ///
+    /// Returns the index of the first bit in the run and the length of the
+    /// run. If there are several runs of the same length, the first one is
+    /// returned.
+    #[inline]
+    pub fn longest_run_of_zeros(self) -> Option<(usize, usize)> {
+        self.inverted().longest_run_of_ones()
+    }
+
+    /// Find the first contiguous run of at least `len` `false` bits in the
+    /// bitmap, and return the index of its first bit.
+    pub fn find_zero_run(self, len: usize) -> Option<usize> {
+        if len == 0 {
+            return Some(0);
+        }
+        let mut next = self.first_false_index();
+        while let Some(start) = next {
+            let end = self.next_index(start).unwrap_or(SIZE);
+            if end - start >= len {
+                return Some(start);
+            }
+            next = self.next_false_index(end);
+        }
+        None
+    }
+
+    /// Invert all the bits in the bitmap.
+    #[inline]
+    pub fn invert(&mut self) {
+        <BitsImpl<SIZE> as Bits>::corrected_invert(&mut self.data);
+    }
+
+    /// Construct a bitmap with all the bits of this bitmap inverted.
+    #[inline]
+    pub fn inverted(mut self) -> Self {
+        self.invert();
+        self
+    }
+
+    /// Reverse the order of the bits in the bitmap, so that the first bit
+    /// becomes the last.
+    #[inline]
+    pub fn reverse(&mut self) {
+        <BitsImpl<SIZE> as Bits>::corrected_reverse(&mut self.data);
+    }
+
+    /// Construct a bitmap with the bits of this bitmap in reverse order.
+    #[inline]
+    pub fn reversed(mut self) -> Self {
+        self.reverse();
+        self
+    }
+
+    /// Shift every bit towards the end of the bitmap by `shift` places, like
+    /// the `<<=` operator, and return the bits which were shifted past the
+    /// end.
+    ///
+    /// The returned bits are positioned where they would land in a following
+    /// bitmap of the same size, so several bitmaps can be chained into one
+    /// long shift register.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// // A 16 bit register made from two 8 bit halves.
+    /// let mut low = Bitmap::<8>::from_value(0b1100_0001);
+    /// let mut high = Bitmap::<8>::from_value(0b0000_0001);
+    /// let carry = low.shl_carry(3);
+    /// high <<= 3;
+    /// high |= carry;
+    /// assert_eq!(low.into_value(), 0b0000_1000);
+    /// assert_eq!(high.into_value(), 0b0000_1110);
+    /// ```
+    pub fn shl_carry(&mut self, shift: usize) -> Self {
+        let carry = if shift <= SIZE {
+            *self >> (SIZE - shift)
+        } else {
+            *self << (shift - SIZE)
+        };
+        *self <<= shift;
+        carry
+    }
+
+    /// Shift every bit towards the start of the bitmap by `shift` places, like
+    /// the `>>=` operator, and return the bits which were shifted past the
+    /// start.
+    ///
+    /// The returned bits are positioned where they would land in a preceding
+    /// bitmap of the same size, so several bitmaps can be chained into one
+    /// long shift register.
+    pub fn shr_carry(&mut self, shift: usize) -> Self {
+        let carry = if shift <= SIZE {
+            *self << (SIZE - shift)
+        } else {
+            *self >> (shift - SIZE)
+        };
+        *self >>= shift;
+        carry
+    }
+
+    /// Combine this bitmap with another bitmap, word by word, using an
+    /// arbitrary binary operation on words.
+    ///
+    /// `f` is called with each word of this bitmap and the corresponding
+    /// word of `other`, as laid out by [`Bitmap::as_words`], and the result
+    /// is stored in this bitmap. Any bits `f` sets past the end of the bitmap
+    /// are cleared afterwards.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let mut bitmap = Bitmap::<200>::from_range(0..150);
+    /// let other = Bitmap::<200>::from_range(100..200);
+    /// // NAND
+    /// bitmap.combine_with(&other, |left, right| !(left & right));
+    /// assert_eq!(bitmap, !Bitmap::from_range(100..150));
+    /// ```
+    #[inline]
+    pub fn combine_with(&mut self, other: &Self, f: impl FnMut(u128, u128) -> u128) {
+        <BitsImpl<SIZE> as Bits>::Store::combine_with(&mut self.data, &other.data, f);
+        *self &= Self::ones();
+    }
+
+    /// Construct a bitmap containing the bits which are `true` in either
+    /// `self` or `other`.
+    #[inline]
+    pub fn union(self, other: Self) -> Self {
+        self | other
+    }
+
+    /// Construct a bitmap containing the bits which are `true` in both `self`
+    /// and `other`.
+    #[inline]
+    pub fn intersection(self, other: Self) -> Self {
+        self & other
+    }
+
+    /// Construct a bitmap containing the bits which are `true` in `self` but
+    /// not in `other`.
+    #[inline]
+    pub fn difference(self, other: Self) -> Self {
+        self - other
+    }
+
+    /// Construct a bitmap containing the bits which are `true` in either
+    /// `self` or `other`, but not in both.
+    #[inline]
+    pub fn symmetric_difference(self, other: Self) -> Self {
+        self ^ other
+    }
+
+    /// Count the number of bits which are `true` in both `self` and `other`.
+    ///
+    /// This is the same as `self.intersection(other).len()`, without
+    /// constructing the intersection.
+    #[inline]
+    pub fn intersection_len(self, other: Self) -> usize {
+        <BitsImpl<SIZE> as Bits>::Store::bit_and_len(&self.data, &other.data)
+    }
+
+    /// Count the number of bits which are `true` in either `self` or
+    /// `other`.
+    ///
+    /// This is the same as `self.union(other).len()`, without constructing
+    /// the union.
+    #[inline]
+    pub fn union_len(self, other: Self) -> usize {
+        <BitsImpl<SIZE> as Bits>::Store::bit_or_len(&self.data, &other.data)
+    }
+
+    /// Count the number of bits which are `true` in `self` but not in
+    /// `other`.
+    ///
+    /// This is the same as `self.difference(other).len()`, without
+    /// constructing the difference.
+    #[inline]
+    pub fn difference_len(self, other: Self) -> usize {
+        <BitsImpl<SIZE> as Bits>::Store::bit_and_not_len(&self.data, &other.data)
+    }
+
+    /// Count the number of bits which differ between `self` and `other`.
+    #[inline]
+    pub fn hamming_distance(self, other: Self) -> usize {
+        <BitsImpl<SIZE> as Bits>::Store::bit_xor_len(&self.data, &other.data)
+    }
+
+    /// Calculate the Jaccard similarity of `self` and `other`, the size of
+    /// their intersection divided by the size of their union.
+    ///
+    /// Two empty bitmaps are considered identical, with a similarity of 1.
+    pub fn jaccard_similarity(self, other: Self) -> f64 {
+        let union = self.union_len(other);
+        if union == 0 {
+            1.0
+        } else {
+            self.intersection_len(other) as f64 / union as f64
+        }
+    }
+
+    /// Calculate the Sørensen-Dice coefficient of `self` and `other`, twice
+    /// the size of their intersection divided by the sum of their sizes.
+    ///
+    /// Two empty bitmaps are considered identical, with a coefficient of 1.
+    pub fn dice_coefficient(self, other: Self) -> f64 {
+        let total = self.len() + other.len();
+        if total == 0 {
+            1.0
+        } else {
+            (2 * self.intersection_len(other)) as f64 / total as f64
+        }
+    }
+
+    /// Calculate the cosine similarity of `self` and `other`, treated as
+    /// vectors of zeros and ones.
+    ///
+    /// Two empty bitmaps are considered identical, with a similarity of 1,
+    /// while an empty bitmap has a similarity of 0 to any other bitmap.
+    #[cfg(feature = "std")]
+    pub fn cosine_similarity(self, other: Self) -> f64 {
+        let (left, right) = (self.len(), other.len());
+        if left == 0 && right == 0 {
+            1.0
+        } else if left == 0 || right == 0 {
+            0.0
+        } else {
+            self.intersection_len(other) as f64 / ((left * right) as f64).sqrt()
+        }
+    }
+
+    /// Test if `self` and `other` have any `true` bits in common.
+    #[inline]
+    pub fn overlaps(self, other: Self) -> bool {
+        <BitsImpl<SIZE> as Bits>::Store::overlaps(&self.data, &other.data)
+    }
+
+    /// Test if `self` and `other` have no `true` bits in common.
+    #[inline]
+    pub fn is_disjoint(self, other: Self) -> bool {
+        !self.overlaps(other)
+    }
+
+    /// Test if every `true` bit in `self` is also `true` in `other`.
+    #[inline]
+    pub fn is_subset(self, other: Self) -> bool {
+        (self - other).is_empty()
+    }
+
+    /// Test if every `true` bit in `other` is also `true` in `self`.
+    #[inline]
+    pub fn is_superset(self, other: Self) -> bool {
+        other.is_subset(self)
+    }
+
+    /// Find the index of the `n`th `true` bit in the bitmap, counting from
+    /// zero, skipping over whole words by their popcount.
+    fn select(self, mut n: usize) -> Option<usize> {
+        let word_size = <BitsImpl<SIZE> as Bits>::Store::bit_size() / self.as_words().len();
+        for (word_index, word) in self.iter_words() {
+            let count = word.count_ones() as usize;
+            if n < count {
+                let mut word = word;
+                for _ in 0..n {
+                    word &= word - 1;
+                }
+                return Some(word_index * word_size + word.trailing_zeros() as usize);
+            }
+            n -= count;
+        }
+        None
+    }
+
+    /// Turn any range of indices into a `Range`, with unbounded ends
+    /// resolved to the bounds of the bitmap.
+    fn resolve_range(range: impl RangeBounds<usize>) -> Range<usize> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => SIZE,
+        };
+        start..end
+    }
+}
+
+impl<'a, const SIZE: usize> IntoIterator for &'a Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Item = usize;
+    type IntoIter = Iter<'a, { SIZE }>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Iter::new(*self)
+    }
+}
+
+/// Construct a bitmap from the indices of its `true` bits.
+///
+/// Panics if any index is out of bounds.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let bitmap: Bitmap<256> = [3, 130, 5].iter().copied().collect();
+/// assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![3, 5, 130]);
+/// ```
+impl<const SIZE: usize> FromIterator<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut bitmap = Self::new();
+        bitmap.extend(iter);
+        bitmap
+    }
+}
+
+/// Set the bits at the given indices to `true`.
+///
+/// Panics if any index is out of bounds.
+impl<const SIZE: usize> Extend<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        for index in iter {
+            assert!(
+                index < SIZE,
+                "index {} is out of bounds for a bitmap of size {}",
+                index,
+                SIZE
+            );
+            self.set(index, true);
+        }
+    }
+}
+
+/// Construct a bitmap from the values of its bits, starting at index 0.
+///
+/// If the iterator yields fewer values than the bitmap has bits, the
+/// remaining bits are `false`, and if it yields more, the rest are ignored.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let bitmap: Bitmap<4> = [true, false, true].iter().copied().collect();
+/// assert_eq!(bitmap.into_value(), 0b0101);
+/// ```
+impl<const SIZE: usize> FromIterator<bool> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn from_iter<I: IntoIterator<Item = bool>>(iter: I) -> Self {
+        let mut bitmap = Self::new();
+        bitmap.extend(iter);
+        bitmap
+    }
+}
+
+/// Set the values of the bits, starting at index 0.
+///
+/// If the iterator yields fewer values than the bitmap has bits, the
+/// remaining bits are left unchanged, and if it yields more, the rest are
+/// ignored.
+impl<const SIZE: usize> Extend<bool> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn extend<I: IntoIterator<Item = bool>>(&mut self, iter: I) {
+        for (index, value) in iter.into_iter().take(SIZE).enumerate() {
+            self.set(index, value);
+        }
+    }
+}
+
+/// Construct a bitmap from an array with the value of each bit.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let bitmap = Bitmap::from([true, false, true]);
+/// assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 2]);
+/// ```
+impl<const SIZE: usize> From<[bool; SIZE]> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn from(values: [bool; SIZE]) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+/// Construct a bitmap from a slice with the value of each bit.
+///
+/// The slice must be exactly as long as the bitmap.
+impl<const SIZE: usize> TryFrom<&[bool]> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Error = Error;
+
+    fn try_from(values: &[bool]) -> Result<Self, Self::Error> {
+        if values.len() == SIZE {
+            Ok(values.iter().copied().collect())
+        } else {
+            Err(Error::InvalidLength {
+                expected: SIZE,
+                found: values.len(),
+            })
+        }
+    }
+}
+
+/// Construct the union of any number of bitmaps.
+///
+/// The union of no bitmaps is an empty bitmap.
+impl<const SIZE: usize> FromIterator<Bitmap<{ SIZE }>> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn from_iter<I: IntoIterator<Item = Bitmap<{ SIZE }>>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(Self::new(), |left, right| left | right)
+    }
+}
+
+/// Construct the union of any number of bitmaps, as with
+/// `FromIterator<Bitmap>`.
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let shards = [Bitmap::<8>::from_value(0b0011), Bitmap::from_value(0b0110)];
+/// assert_eq!(shards.iter().copied().sum::<Bitmap<8>>().into_value(), 0b0111);
+/// assert_eq!(shards.iter().copied().product::<Bitmap<8>>().into_value(), 0b0010);
+/// ```
+impl<const SIZE: usize> Sum for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.collect()
+    }
+}
+
+/// Construct the intersection of any number of bitmaps.
+///
+/// The intersection of no bitmaps is a bitmap with every bit set.
+impl<const SIZE: usize> Product for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::ones(), |left, right| left & right)
+    }
+}
+
+impl<const SIZE: usize> Index<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = bool;
+
+    /// Get the value of the bit at a given index.
+    ///
+    /// Panics if the index is out of bounds.
+    fn index(&self, index: usize) -> &Self::Output {
+        assert!(
+            index < SIZE,
+            "index {} is out of bounds for a bitmap of size {}",
+            index,
+            SIZE
+        );
+        if self.get(index) {
+            &true
+        } else {
+            &false
+        }
+    }
+}
+
+impl<const SIZE: usize> BitAnd for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn bitand(mut self, rhs: Self) -> Self::Output {
+        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
+        self
+    }
+}
+
+impl<const SIZE: usize> BitOr for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn bitor(mut self, rhs: Self) -> Self::Output {
+        <BitsImpl<SIZE> as Bits>::Store::bit_or(&mut self.data, &rhs.data);
+        self
+    }
+}
+
+impl<const SIZE: usize> BitXor for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn bitxor(mut self, rhs: Self) -> Self::Output {
+        <BitsImpl<SIZE> as Bits>::Store::bit_xor(&mut self.data, &rhs.data);
+        self
+    }
+}
+
+impl<const SIZE: usize> Sub for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn sub(mut self, mut rhs: Self) -> Self::Output {
+        <BitsImpl<SIZE> as Bits>::Store::invert(&mut rhs.data);
+        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
+        self
+    }
+}
+
+impl<const SIZE: usize> Not for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn not(self) -> Self::Output {
+        self.inverted()
+    }
+}
+
+impl<const SIZE: usize> Shl<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn shl(mut self, rhs: usize) -> Self::Output {
+        self <<= rhs;
+        self
+    }
+}
+
+impl<const SIZE: usize> Shr<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Output = Self;
+    fn shr(mut self, rhs: usize) -> Self::Output {
+        self >>= rhs;
+        self
+    }
+}
+
+impl<const SIZE: usize> ShlAssign<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    /// Move every bit towards the end of the bitmap by `rhs` places. Bits
+    /// moved past the end are discarded.
+    fn shl_assign(&mut self, rhs: usize) {
+        <BitsImpl<SIZE> as Bits>::corrected_shift_left(&mut self.data, rhs);
+    }
+}
+
+impl<const SIZE: usize> ShrAssign<usize> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    /// Move every bit towards the start of the bitmap by `rhs` places. Bits
+    /// moved past the start are discarded.
+    fn shr_assign(&mut self, rhs: usize) {
+        <BitsImpl<SIZE> as Bits>::Store::shift_right(&mut self.data, rhs);
+    }
+}
+
+impl<const SIZE: usize> BitAndAssign for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn bitand_assign(&mut self, rhs: Self) {
+        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
+    }
+}
+
+impl<const SIZE: usize> BitOrAssign for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn bitor_assign(&mut self, rhs: Self) {
+        <BitsImpl<SIZE> as Bits>::Store::bit_or(&mut self.data, &rhs.data);
+    }
+}
+
+impl<const SIZE: usize> BitXorAssign for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn bitxor_assign(&mut self, rhs: Self) {
+        <BitsImpl<SIZE> as Bits>::Store::bit_xor(&mut self.data, &rhs.data);
+    }
+}
+
+impl<const SIZE: usize> SubAssign for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn sub_assign(&mut self, mut rhs: Self) {
+        <BitsImpl<SIZE> as Bits>::Store::invert(&mut rhs.data);
+        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
+    }
+}
+
+impl<const SIZE: usize> BitAndAssign<&Self> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn bitand_assign(&mut self, rhs: &Self) {
+        <BitsImpl<SIZE> as Bits>::Store::bit_and(&mut self.data, &rhs.data);
+    }
+}
+
+impl<const SIZE: usize> BitOrAssign<&Self> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn bitor_assign(&mut self, rhs: &Self) {
+        <BitsImpl<SIZE> as Bits>::Store::bit_or(&mut self.data, &rhs.data);
+    }
+}
+
+impl<const SIZE: usize> BitXorAssign<&Self> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn bitxor_assign(&mut self, rhs: &Self) {
+        <BitsImpl<SIZE> as Bits>::Store::bit_xor(&mut self.data, &rhs.data);
+    }
+}
+
+// Each integer type only converts into the bitmaps at least as wide as it,
+// so the sizes are grouped by the widest integer they can hold.
+macro_rules! from_uint {
+    ([$($uint:ty),*] $sizes:tt) => {
+        $(
+            from_uint!(@impl $uint, $sizes);
+        )*
+    };
+    (@impl $uint:ty, ($($size:expr),*)) => {
+        $(
+            /// Construct a bitmap from the bits of an unsigned integer, with
+            /// its least significant bit at index 0, and every bit past the
+            /// width of the integer set to `false`.
+            impl From<$uint> for Bitmap<$size> {
+                fn from(value: $uint) -> Self {
+                    let mut bitmap = Self::new();
+                    bitmap.set_bits(0..<$uint>::BITS as usize, value as u128);
+                    bitmap
+                }
+            }
+        )*
+    };
+}
+
+from_uint!([u8](8, 9, 10, 11, 12, 13, 14, 15));
+from_uint!([u8, u16](
+    16, 17, 18, 19, 20, 21, 22, 23, 24, 25, 26, 27, 28, 29, 30, 31
+));
+from_uint!([u8, u16, u32](
+    32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49, 50, 51, 52, 53, 54, 55,
+    56, 57, 58, 59, 60, 61, 62, 63
+));
+from_uint!([u8, u16, u32, u64](
+    64, 65, 66, 67, 68, 69, 70, 71, 72, 73, 74, 75, 76, 77, 78, 79, 80, 81, 82, 83, 84, 85, 86, 87,
+    88, 89, 90, 91, 92, 93, 94, 95, 96, 97, 98, 99, 100, 101, 102, 103, 104, 105, 106, 107, 108,
+    109, 110, 111, 112, 113, 114, 115, 116, 117, 118, 119, 120, 121, 122, 123, 124, 125, 126, 127
+));
+from_uint!([u8, u16, u32, u64, u128](
+    128, 129, 130, 131, 132, 133, 134, 135, 136, 137, 138, 139, 140, 141, 142, 143, 144, 145, 146,
+    147, 148, 149, 150, 151, 152, 153, 154, 155, 156, 157, 158, 159, 160, 161, 162, 163, 164, 165,
+    166, 167, 168, 169, 170, 171, 172, 173, 174, 175, 176, 177, 178, 179, 180, 181, 182, 183, 184,
+    185, 186, 187, 188, 189, 190, 191, 192, 193, 194, 195, 196, 197, 198, 199, 200, 201, 202, 203,
+    204, 205, 206, 207, 208, 209, 210, 211, 212, 213, 214, 215, 216, 217, 218, 219, 220, 221, 222,
+    223, 224, 225, 226, 227, 228, 229, 230, 231, 232, 233, 234, 235, 236, 237, 238, 239, 240, 241,
+    242, 243, 244, 245, 246, 247, 248, 249, 250, 251, 252, 253, 254, 255, 256, 257, 258, 259, 260,
+    261, 262, 263, 264, 265, 266, 267, 268, 269, 270, 271, 272, 273, 274, 275, 276, 277, 278, 279,
+    280, 281, 282, 283, 284, 285, 286, 287, 288, 289, 290, 291, 292, 293, 294, 295, 296, 297, 298,
+    299, 300, 301, 302, 303, 304, 305, 306, 307, 308, 309, 310, 311, 312, 313, 314, 315, 316, 317,
+    318, 319, 320, 321, 322, 323, 324, 325, 326, 327, 328, 329, 330, 331, 332, 333, 334, 335, 336,
+    337, 338, 339, 340, 341, 342, 343, 344, 345, 346, 347, 348, 349, 350, 351, 352, 353, 354, 355,
+    356, 357, 358, 359, 360, 361, 362, 363, 364, 365, 366, 367, 368, 369, 370, 371, 372, 373, 374,
+    375, 376, 377, 378, 379, 380, 381, 382, 383, 384, 385, 386, 387, 388, 389, 390, 391, 392, 393,
+    394, 395, 396, 397, 398, 399, 400, 401, 402, 403, 404, 405, 406, 407, 408, 409, 410, 411, 412,
+    413, 414, 415, 416, 417, 418, 419, 420, 421, 422, 423, 424, 425, 426, 427, 428, 429, 430, 431,
+    432, 433, 434, 435, 436, 437, 438, 439, 440, 441, 442, 443, 444, 445, 446, 447, 448, 449, 450,
+    451, 452, 453, 454, 455, 456, 457, 458, 459, 460, 461, 462, 463, 464, 465, 466, 467, 468, 469,
+    470, 471, 472, 473, 474, 475, 476, 477, 478, 479, 480, 481, 482, 483, 484, 485, 486, 487, 488,
+    489, 490, 491, 492, 493, 494, 495, 496, 497, 498, 499, 500, 501, 502, 503, 504, 505, 506, 507,
+    508, 509, 510, 511, 512, 513, 514, 515, 516, 517, 518, 519, 520, 521, 522, 523, 524, 525, 526,
+    527, 528, 529, 530, 531, 532, 533, 534, 535, 536, 537, 538, 539, 540, 541, 542, 543, 544, 545,
+    546, 547, 548, 549, 550, 551, 552, 553, 554, 555, 556, 557, 558, 559, 560, 561, 562, 563, 564,
+    565, 566, 567, 568, 569, 570, 571, 572, 573, 574, 575, 576, 577, 578, 579, 580, 581, 582, 583,
+    584, 585, 586, 587, 588, 589, 590, 591, 592, 593, 594, 595, 596, 597, 598, 599, 600, 601, 602,
+    603, 604, 605, 606, 607, 608, 609, 610, 611, 612, 613, 614, 615, 616, 617, 618, 619, 620, 621,
+    622, 623, 624, 625, 626, 627, 628, 629, 630, 631, 632, 633, 634, 635, 636, 637, 638, 639, 640,
+    641, 642, 643, 644, 645, 646, 647, 648, 649, 650, 651, 652, 653, 654, 655, 656, 657, 658, 659,
+    660, 661, 662, 663, 664, 665, 666, 667, 668, 669, 670, 671, 672, 673, 674, 675, 676, 677, 678,
+    679, 680, 681, 682, 683, 684, 685, 686, 687, 688, 689, 690, 691, 692, 693, 694, 695, 696, 697,
+    698, 699, 700, 701, 702, 703, 704, 705, 706, 707, 708, 709, 710, 711, 712, 713, 714, 715, 716,
+    717, 718, 719, 720, 721, 722, 723, 724, 725, 726, 727, 728, 729, 730, 731, 732, 733, 734, 735,
+    736, 737, 738, 739, 740, 741, 742, 743, 744, 745, 746, 747, 748, 749, 750, 751, 752, 753, 754,
+    755, 756, 757, 758, 759, 760, 761, 762, 763, 764, 765, 766, 767, 768, 769, 770, 771, 772, 773,
+    774, 775, 776, 777, 778, 779, 780, 781, 782, 783, 784, 785, 786, 787, 788, 789, 790, 791, 792,
+    793, 794, 795, 796, 797, 798, 799, 800, 801, 802, 803, 804, 805, 806, 807, 808, 809, 810, 811,
+    812, 813, 814, 815, 816, 817, 818, 819, 820, 821, 822, 823, 824, 825, 826, 827, 828, 829, 830,
+    831, 832, 833, 834, 835, 836, 837, 838, 839, 840, 841, 842, 843, 844, 845, 846, 847, 848, 849,
+    850, 851, 852, 853, 854, 855, 856, 857, 858, 859, 860, 861, 862, 863, 864, 865, 866, 867, 868,
+    869, 870, 871, 872, 873, 874, 875, 876, 877, 878, 879, 880, 881, 882, 883, 884, 885, 886, 887,
+    888, 889, 890, 891, 892, 893, 894, 895, 896, 897, 898, 899, 900, 901, 902, 903, 904, 905, 906,
+    907, 908, 909, 910, 911, 912, 913, 914, 915, 916, 917, 918, 919, 920, 921, 922, 923, 924, 925,
+    926, 927, 928, 929, 930, 931, 932, 933, 934, 935, 936, 937, 938, 939, 940, 941, 942, 943, 944,
+    945, 946, 947, 948, 949, 950, 951, 952, 953, 954, 955, 956, 957, 958, 959, 960, 961, 962, 963,
+    964, 965, 966, 967, 968, 969, 970, 971, 972, 973, 974, 975, 976, 977, 978, 979, 980, 981, 982,
+    983, 984, 985, 986, 987, 988, 989, 990, 991, 992, 993, 994, 995, 996, 997, 998, 999, 1000,
+    1001, 1002, 1003, 1004, 1005, 1006, 1007, 1008, 1009, 1010, 1011, 1012, 1013, 1014, 1015, 1016,
+    1017, 1018, 1019, 1020, 1021, 1022, 1023, 1024
+));
+
+impl From<[u128; 2]> for Bitmap<256> {
+    fn from(data: [u128; 2]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<[u128; 3]> for Bitmap<384> {
+    fn from(data: [u128; 3]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<[u128; 4]> for Bitmap<512> {
+    fn from(data: [u128; 4]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<[u128; 5]> for Bitmap<640> {
+    fn from(data: [u128; 5]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<[u128; 6]> for Bitmap<768> {
+    fn from(data: [u128; 6]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<[u128; 7]> for Bitmap<896> {
+    fn from(data: [u128; 7]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<[u128; 8]> for Bitmap<1024> {
+    fn from(data: [u128; 8]) -> Self {
+        Bitmap { data }
+    }
+}
+
+impl From<Bitmap<256>> for [u128; 2] {
+    fn from(bitmap: Bitmap<256>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+impl From<Bitmap<384>> for [u128; 3] {
+    fn from(bitmap: Bitmap<384>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+impl From<Bitmap<512>> for [u128; 4] {
+    fn from(bitmap: Bitmap<512>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+impl From<Bitmap<640>> for [u128; 5] {
+    fn from(bitmap: Bitmap<640>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+impl From<Bitmap<768>> for [u128; 6] {
+    fn from(bitmap: Bitmap<768>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+impl From<Bitmap<896>> for [u128; 7] {
+    fn from(bitmap: Bitmap<896>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+impl From<Bitmap<1024>> for [u128; 8] {
+    fn from(bitmap: Bitmap<1024>) -> Self {
+        bitmap.into_value()
+    }
+}
+
+/// An iterator over the indices in a bitmap which are `true`.
+///
+/// This yields a sequence of `usize` indices, not their contents (which are
+/// always `true` anyway, by definition).
+///
+/// # Examples
+///
+/// ```rust
+/// # use bitmaps::Bitmap;
+/// let mut bitmap: Bitmap<10> = Bitmap::new();
+/// bitmap.set(3, true);
+/// bitmap.set(5, true);
+/// bitmap.set(8, true);
+/// let true_indices: Vec<usize> = bitmap.into_iter().collect();
+/// assert_eq!(vec![3, 5, 8], true_indices);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Iter<'a, const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    remaining: usize,
+    /// The first word of the store which may still have bits set.
+    front: usize,
+    /// One past the last word of the store which may still have bits set.
+    back: usize,
+    data: Bitmap<{ SIZE }>,
+    phantom: PhantomData<&'a Bitmap<{ SIZE }>>,
+}
+
+impl<'a, const SIZE: usize> Iter<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn new(data: Bitmap<{ SIZE }>) -> Self {
+        Iter {
+            remaining: data.len(),
+            front: 0,
+            back: data.as_words().len(),
+            data,
+            phantom: PhantomData,
+        }
+    }
+
+    /// The number of bits in each word of the store.
+    fn word_size(&self) -> usize {
+        <BitsImpl<SIZE> as Bits>::Store::bit_size() / self.data.as_words().len()
+    }
+}
+
+impl<'a, const SIZE: usize> Iterator for Iter<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        // The indices still to be yielded are exactly the bits left set in
+        // our copy of the bitmap, so we clear each one as we go, and move
+        // past each word once it's empty so it's never scanned again.
+        while self.front < self.back {
+            let word: u128 = self.data.as_words()[self.front].into();
+            if word != 0 {
+                let index = self.front * self.word_size() + word.trailing_zeros() as usize;
+                self.data.set(index, false);
+                self.remaining -= 1;
+                return Some(index);
+            }
+            self.front += 1;
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+
+    fn count(self) -> usize {
+        self.remaining
+    }
+
+    fn last(self) -> Option<Self::Item> {
+        self.data.last_index()
+    }
+
+    fn min(self) -> Option<Self::Item> {
+        self.data.first_index()
+    }
+
+    fn max(self) -> Option<Self::Item> {
+        self.data.last_index()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        match self.data.select(n) {
+            Some(index) => {
+                self.data.set_range(..=index, false);
+                self.remaining -= n + 1;
+                Some(index)
+            }
+            None => {
+                self.data.clear();
+                self.remaining = 0;
+                None
+            }
+        }
+    }
+}
+
+impl<'a, const SIZE: usize> DoubleEndedIterator for Iter<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while self.front < self.back {
+            let word: u128 = self.data.as_words()[self.back - 1].into();
+            if word != 0 {
+                let bit = 127 - word.leading_zeros() as usize;
+                let index = (self.back - 1) * self.word_size() + bit;
+                self.data.set(index, false);
+                self.remaining -= 1;
+                return Some(index);
+            }
+            self.back -= 1;
+        }
+        None
+    }
+}
+
+impl<'a, const SIZE: usize> ExactSizeIterator for Iter<'a, SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+impl<'a, const SIZE: usize> FusedIterator for Iter<'a, SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+/// An iterator which clears the `true` bits of a [`Bitmap`] while yielding
+/// their indices.
+///
+/// This is obtained from [`Bitmap::drain`].
+#[derive(Debug)]
+pub struct Drain<'a, const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    data: &'a mut Bitmap<{ SIZE }>,
+}
+
+impl<'a, const SIZE: usize> Iterator for Drain<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.data.first_index()?;
+        self.data.set(index, false);
+        Some(index)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.data.len();
+        (len, Some(len))
+    }
+}
+
+impl<'a, const SIZE: usize> DoubleEndedIterator for Drain<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let index = self.data.last_index()?;
+        self.data.set(index, false);
+        Some(index)
+    }
+}
+
+impl<'a, const SIZE: usize> ExactSizeIterator for Drain<'a, SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+impl<'a, const SIZE: usize> FusedIterator for Drain<'a, SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+impl<'a, const SIZE: usize> Drop for Drain<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn drop(&mut self) {
+        self.data.clear();
+    }
+}
+
+/// An iterator over every subset of the `true` bits in a [`Bitmap`].
+///
+/// This is obtained from [`Bitmap::subsets`].
+#[derive(Clone, Debug)]
+pub struct Subsets<const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    mask: Bitmap<{ SIZE }>,
+    next: Option<Bitmap<{ SIZE }>>,
+}
+
+impl<const SIZE: usize> Iterator for Subsets<SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Item = Bitmap<{ SIZE }>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        // Adding one to the subset, counting only the bits in the mask: the
+        // lowest bit of the mask missing from the subset gets set, and every
+        // bit below it gets cleared.
+        self.next = (self.mask - current).first_index().map(|index| {
+            let mut next = current;
+            next.set_range(..index, false);
+            next.set(index, true);
+            next
+        });
+        Some(current)
+    }
+}
+
+impl<const SIZE: usize> FusedIterator for Subsets<SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+/// An iterator over every [`Bitmap`] with a given number of `true` bits.
+///
+/// This is obtained from [`Bitmap::combinations`].
+#[derive(Clone, Debug)]
+pub struct Combinations<const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    next: Option<Bitmap<{ SIZE }>>,
+}
+
+impl<const SIZE: usize> Iterator for Combinations<SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Item = Bitmap<{ SIZE }>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        // Move the highest bit of the lowest run of `true` bits up by one,
+        // and the rest of that run down to the bottom of the bitmap.
+        self.next = current.first_index().and_then(|start| {
+            let end = current.next_false_index(start)?;
+            let mut next = current;
+            next.set_range(start..end, false);
+            next.set(end, true);
+            next.set_range(..end - start - 1, true);
+            Some(next)
+        });
+        Some(current)
+    }
+}
+
+impl<const SIZE: usize> FusedIterator for Combinations<SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+/// A cursor over a [`Bitmap`], which can move back and forth between its
+/// `true` and `false` bits.
+///
+/// The cursor's position is always between two bits, or at either end of
+/// the bitmap: at position `n`, bit `n - 1` is behind it and bit `n` is in
+/// front of it. Searching forwards moves the cursor past the bit found, and
+/// searching backwards moves it in front of the bit found, so alternating
+/// between the two finds the same bit.
+///
+/// This is obtained from [`Bitmap::cursor`].
+#[derive(Clone, Debug)]
+pub struct Cursor<'a, const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    data: &'a Bitmap<{ SIZE }>,
+    position: usize,
+}
+
+impl<'a, const SIZE: usize> Cursor<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    /// Get the position of the cursor.
+    #[inline]
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Move the cursor to a given position, which is clamped to the size of
+    /// the bitmap.
+    #[inline]
+    pub fn seek(&mut self, position: usize) {
+        self.position = position.min(SIZE);
+    }
+
+    /// Get the value of the bit in front of the cursor, or `None` if the
+    /// cursor is at the end of the bitmap.
+    #[inline]
+    pub fn peek(&self) -> Option<bool> {
+        if self.position < SIZE {
+            Some(self.data.get(self.position))
+        } else {
+            None
+        }
+    }
+
+    /// Find the first `true` bit in front of the cursor, and move the
+    /// cursor past it.
+    ///
+    /// If there is no such bit, returns `None` and leaves the cursor where
+    /// it is.
+    pub fn next_one(&mut self) -> Option<usize> {
+        let index = match self.position {
+            0 => self.data.first_index(),
+            position => self.data.next_index(position - 1),
+        }?;
+        self.position = index + 1;
+        Some(index)
+    }
+
+    /// Find the first `false` bit in front of the cursor, and move the
+    /// cursor past it.
+    ///
+    /// If there is no such bit, returns `None` and leaves the cursor where
+    /// it is.
+    pub fn next_zero(&mut self) -> Option<usize> {
+        let index = match self.position {
+            0 => self.data.first_false_index(),
+            position => self.data.next_false_index(position - 1),
+        }?;
+        self.position = index + 1;
+        Some(index)
+    }
+
+    /// Find the last `true` bit behind the cursor, and move the cursor in
+    /// front of it.
+    ///
+    /// If there is no such bit, returns `None` and leaves the cursor where
+    /// it is.
+    pub fn prev_one(&mut self) -> Option<usize> {
+        let index = self.data.prev_index(self.position)?;
+        self.position = index;
+        Some(index)
+    }
+
+    /// Find the last `false` bit behind the cursor, and move the cursor in
+    /// front of it.
+    ///
+    /// If there is no such bit, returns `None` and leaves the cursor where
+    /// it is.
+    pub fn prev_zero(&mut self) -> Option<usize> {
+        let index = self.data.prev_false_index(self.position)?;
+        self.position = index;
+        Some(index)
+    }
+}
+
+/// An iterator over the maximal runs of `true` bits in a [`Bitmap`], as
+/// ranges of indices.
+///
+/// This is obtained from [`Bitmap::ranges`] or [`Bitmap::zero_ranges`].
+#[derive(Clone, Debug)]
+pub struct Ranges<const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    data: Bitmap<{ SIZE }>,
+}
+
+impl<const SIZE: usize> Iterator for Ranges<SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Item = Range<usize>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.data.first_index()?;
+        let end = self.data.next_false_index(start).unwrap_or(SIZE);
+        self.data.set_range(start..end, false);
+        Some(start..end)
+    }
+}
+
+impl<const SIZE: usize> DoubleEndedIterator for Ranges<SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let end = self.data.last_index()? + 1;
+        let start = self
+            .data
+            .prev_false_index(end - 1)
+            .map_or(0, |index| index + 1);
+        self.data.set_range(start..end, false);
+        Some(start..end)
+    }
+}
+
+impl<const SIZE: usize> FusedIterator for Ranges<SIZE> where BitsImpl<{ SIZE }>: Bits {}
+
+/// A mutable reference to a single bit in a [`Bitmap`].
+///
+/// This is obtained from [`Bitmap::bit_mut`].
+#[derive(Debug)]
+pub struct BitRef<'a, const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    bitmap: &'a mut Bitmap<{ SIZE }>,
+    index: usize,
+}
+
+impl<'a, const SIZE: usize> BitRef<'a, SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    /// Get the index of the bit this reference points to.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// Get the value of the bit.
+    #[inline]
+    pub fn get(&self) -> bool {
+        self.bitmap.get(self.index)
+    }
+
+    /// Set the value of the bit.
+    #[inline]
+    pub fn set(&mut self, value: bool) {
+        self.bitmap.set(self.index, value);
+    }
+
+    /// Flip the value of the bit.
+    ///
+    /// Returns the previous value of the bit.
+    #[inline]
+    pub fn toggle(&mut self) -> bool {
+        self.bitmap.toggle(self.index)
+    }
+
+    /// Set the value of the bit, returning its previous value.
+    #[inline]
+    pub fn replace(&mut self, value: bool) -> bool {
+        self.bitmap.set(self.index, value)
+    }
+}
+
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+#[allow(clippy::cast_ptr_alignment)]
+mod x86_arch {
+    use super::*;
+    #[cfg(target_arch = "x86")]
+    use core::arch::x86::*;
+    #[cfg(target_arch = "x86_64")]
+    use core::arch::x86_64::*;
+
+    impl Bitmap<128> {
+        #[target_feature(enable = "sse2")]
+        pub unsafe fn load_m128i(&self) -> __m128i {
+            _mm_loadu_si128(&self.data as *const _ as *const __m128i)
+        }
+    }
+
+    impl Bitmap<256> {
+        #[target_feature(enable = "sse2")]
+        pub unsafe fn load_m128i(&self) -> [__m128i; 2] {
+            let ptr = &self.data as *const _ as *const __m128i;
+            [_mm_loadu_si128(ptr), _mm_loadu_si128(ptr.add(1))]
+        }
+
+        #[target_feature(enable = "avx")]
+        pub unsafe fn load_m256i(&self) -> __m256i {
+            _mm256_loadu_si256(&self.data as *const _ as *const __m256i)
+        }
+    }
+
+    impl Bitmap<512> {
+        #[target_feature(enable = "sse2")]
+        pub unsafe fn load_m128i(&self) -> [__m128i; 4] {
+            let ptr = &self.data as *const _ as *const __m128i;
+            [
+                _mm_loadu_si128(ptr),
+                _mm_loadu_si128(ptr.add(1)),
+                _mm_loadu_si128(ptr.add(2)),
+                _mm_loadu_si128(ptr.add(3)),
+            ]
+        }
+
+        #[target_feature(enable = "avx")]
+        pub unsafe fn load_m256i(&self) -> [__m256i; 2] {
+            let ptr = &self.data as *const _ as *const __m256i;
+            [_mm256_loadu_si256(ptr), _mm256_loadu_si256(ptr.add(1))]
+        }
+    }
+
+    impl Bitmap<768> {
+        #[target_feature(enable = "sse2")]
+        pub unsafe fn load_m128i(&self) -> [__m128i; 6] {
+            let ptr = &self.data as *const _ as *const __m128i;
+            [
+                _mm_loadu_si128(ptr),
+                _mm_loadu_si128(ptr.add(1)),
+                _mm_loadu_si128(ptr.add(2)),
+                _mm_loadu_si128(ptr.add(3)),
+                _mm_loadu_si128(ptr.add(4)),
+                _mm_loadu_si128(ptr.add(5)),
+            ]
+        }
+
+        #[target_feature(enable = "avx")]
+        pub unsafe fn load_m256i(&self) -> [__m256i; 3] {
+            let ptr = &self.data as *const _ as *const __m256i;
+            [
+                _mm256_loadu_si256(ptr),
+                _mm256_loadu_si256(ptr.add(1)),
+                _mm256_loadu_si256(ptr.add(2)),
+            ]
+        }
+    }
+
+    impl Bitmap<1024> {
+        #[target_feature(enable = "sse2")]
+        pub unsafe fn load_m128i(&self) -> [__m128i; 8] {
+            let ptr = &self.data as *const _ as *const __m128i;
+            [
+                _mm_loadu_si128(ptr),
+                _mm_loadu_si128(ptr.add(1)),
+                _mm_loadu_si128(ptr.add(2)),
+                _mm_loadu_si128(ptr.add(3)),
+                _mm_loadu_si128(ptr.add(4)),
+                _mm_loadu_si128(ptr.add(5)),
+                _mm_loadu_si128(ptr.add(6)),
+                _mm_loadu_si128(ptr.add(7)),
+            ]
+        }
+
+        #[target_feature(enable = "avx")]
+        pub unsafe fn load_m256i(&self) -> [__m256i; 4] {
+            let ptr = &self.data as *const _ as *const __m256i;
+            [
+                _mm256_loadu_si256(ptr),
+                _mm256_loadu_si256(ptr.add(1)),
+                _mm256_loadu_si256(ptr.add(2)),
+                _mm256_loadu_si256(ptr.add(3)),
+            ]
+        }
+    }
+
+    impl From<__m128i> for Bitmap<128> {
+        fn from(data: __m128i) -> Self {
+            Self {
+                data: unsafe { core::mem::transmute::<__m128i, u128>(data) },
+            }
+        }
+    }
+
+    impl From<__m256i> for Bitmap<256> {
+        fn from(data: __m256i) -> Self {
+            Self {
+                data: unsafe { core::mem::transmute::<__m256i, [u128; 2]>(data) },
+            }
+        }
+    }
+
+    impl From<Bitmap<128>> for __m128i {
+        fn from(data: Bitmap<128>) -> Self {
+            unsafe { data.load_m128i() }
+        }
+    }
+
+    impl From<Bitmap<256>> for __m256i {
+        fn from(data: Bitmap<256>) -> Self {
+            unsafe { data.load_m256i() }
+        }
+    }
+
+    macro_rules! simd_array_from {
+        ($size:expr, $words:expr, $simd:ty, $len:expr) => {
+            impl From<[$simd; $len]> for Bitmap<$size> {
+                fn from(data: [$simd; $len]) -> Self {
+                    Self {
+                        data: unsafe {
+                            core::mem::transmute::<[$simd; $len], [u128; $words]>(data)
+                        },
+                    }
+                }
+            }
+
+            impl From<Bitmap<$size>> for [$simd; $len] {
+                fn from(data: Bitmap<$size>) -> Self {
+                    unsafe { core::mem::transmute::<[u128; $words], [$simd; $len]>(data.data) }
+                }
+            }
+        };
+    }
+
+    simd_array_from!(512, 4, __m128i, 4);
+    simd_array_from!(512, 4, __m256i, 2);
+    simd_array_from!(768, 6, __m128i, 6);
+    simd_array_from!(768, 6, __m256i, 3);
+    simd_array_from!(1024, 8, __m128i, 8);
+    simd_array_from!(1024, 8, __m256i, 4);
+
+    #[cfg(test)]
+    mod test {
+        use super::*;
+
+        struct AlignmentTester<const B: usize>
+        where
+            BitsImpl<B>: Bits,
+        {
+            _byte: u8,
+            bits: Bitmap<B>,
+        }
+
+        #[test]
+        fn load_128() {
+            let mut t: AlignmentTester<128> = AlignmentTester {
+                _byte: 0,
+                bits: Bitmap::new(),
+            };
+            t.bits.set(5, true);
+            let m = unsafe { t.bits.load_m128i() };
+            let mut bits: Bitmap<128> = m.into();
+            assert!(bits.set(5, false));
+            assert!(bits.is_empty());
+        }
+
+        #[test]
+        fn load_256() {
+            let mut t: AlignmentTester<256> = AlignmentTester {
+                _byte: 0,
+                bits: Bitmap::new(),
+            };
+            t.bits.set(5, true);
+            let m = unsafe { t.bits.load_m256i() };
+            let mut bits: Bitmap<256> = m.into();
+            assert!(bits.set(5, false));
+            assert!(bits.is_empty());
+        }
+
+        #[test]
+        fn simd_arrays() {
+            let mut bitmap = Bitmap::<1024>::new();
+            for index in [0, 127, 128, 300, 1023] {
+                bitmap.set(index, true);
+            }
+            let m128: [__m128i; 8] = bitmap.into();
+            assert_eq!(Bitmap::<1024>::from(m128), bitmap);
+            let m256: [__m256i; 4] = bitmap.into();
+            assert_eq!(Bitmap::<1024>::from(m256), bitmap);
+
+            let bitmap = Bitmap::<768>::from_range(100..700);
+            let m128: [__m128i; 6] = bitmap.into();
+            assert_eq!(Bitmap::<768>::from(m128), bitmap);
+            let m256: [__m256i; 3] = bitmap.into();
+            assert_eq!(Bitmap::<768>::from(m256), bitmap);
+
+            let bitmap = Bitmap::<512>::from_range(200..300);
+            let m128: [__m128i; 4] = bitmap.into();
+            assert_eq!(Bitmap::<512>::from(m128), bitmap);
+            let m256: [__m256i; 2] = bitmap.into();
+            assert_eq!(Bitmap::<512>::from(m256), bitmap);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "std"))]
+    extern crate std;
+
+    use super::*;
+    use proptest::collection::{btree_set, vec};
+    use proptest::proptest;
+    use std::collections::BTreeSet;
+    use std::prelude::rust_2021::*;
+    use std::{format, vec};
+
+    #[test]
+    fn last_index_1() {
+        let mut bitmap = Bitmap::<1>::new();
+        assert_eq!(bitmap.last_index(), None);
+        bitmap.set(0, true);
+        assert_eq!(bitmap.last_index(), Some(0));
+    }
+
+    #[test]
+    fn next_index_1() {
+        let mut bitmap = Bitmap::<1>::new();
+        bitmap.set(0, true);
+        assert_eq!(bitmap.next_index(0), None);
+        assert_eq!(bitmap.next_index(1), None);
+    }
+
+    #[test]
+    fn prev_index_1() {
+        let mut bitmap = Bitmap::<1>::new();
+        assert_eq!(bitmap.prev_index(1), None);
+        bitmap.set(0, true);
+        assert_eq!(bitmap.prev_index(0), None);
+        assert_eq!(bitmap.prev_index(1), Some(0));
+    }
+
+    #[test]
+    fn first_false_index_1() {
+        let mut bitmap = Bitmap::<1>::new();
+        assert_eq!(bitmap.first_false_index(), Some(0));
+        bitmap.set(0, true);
+        assert_eq!(bitmap.first_false_index(), None);
+    }
+
+    #[test]
+    fn is_full() {
+        let mut bitmap = Bitmap::<1>::new();
+        assert!(!bitmap.is_full());
+        bitmap.set(0, true);
+        assert!(bitmap.is_full());
+
+        let mut bitmap = !Bitmap::<10>::new();
+        assert!(bitmap.is_full());
+        bitmap.set(9, false);
+        assert!(!bitmap.is_full());
+
+        let mut bitmap = !Bitmap::<200>::new();
+        assert!(bitmap.is_full());
+        bitmap.set(199, false);
+        assert!(!bitmap.is_full());
+
+        let mut bitmap = !Bitmap::<1024>::new();
+        assert!(bitmap.is_full());
+        bitmap.set(0, false);
+        assert!(!bitmap.is_full());
+    }
+
+    #[test]
+    fn overlaps() {
+        let a = Bitmap::<1>::ones();
+        assert!(a.overlaps(a));
+        assert!(!a.overlaps(Bitmap::new()));
+        let a = Bitmap::<10>::from_range(2..5);
+        assert!(a.overlaps(Bitmap::from_range(4..8)));
+        assert!(!a.overlaps(Bitmap::from_range(5..8)));
+    }
+
+    #[test]
+    fn combined_len() {
+        let a = Bitmap::<10>::from_range(2..5);
+        let b = Bitmap::<10>::from_range(4..8);
+        assert_eq!(a.intersection_len(b), 1);
+        assert_eq!(a.union_len(b), 6);
+        assert_eq!(a.difference_len(b), 2);
+        let a = Bitmap::<1>::ones();
+        let b = Bitmap::<1>::new();
+        assert_eq!(a.intersection_len(b), 0);
+        assert_eq!(a.union_len(b), 1);
+        assert_eq!(a.difference_len(b), 1);
+        assert_eq!(b.difference_len(a), 0);
+        assert_eq!(a.hamming_distance(b), 1);
+        assert_eq!(a.hamming_distance(a), 0);
+    }
+
+    #[test]
+    fn similarity() {
+        let empty = Bitmap::<200>::new();
+        let a = Bitmap::<200>::from_range(0..100);
+        let b = Bitmap::<200>::from_range(50..150);
+        assert_eq!(a.jaccard_similarity(b), 50.0 / 150.0);
+        assert_eq!(a.dice_coefficient(b), 0.5);
+        assert_eq!(a.jaccard_similarity(a), 1.0);
+        assert_eq!(a.dice_coefficient(a), 1.0);
+        assert_eq!(a.jaccard_similarity(empty), 0.0);
+        assert_eq!(a.dice_coefficient(empty), 0.0);
+        assert_eq!(empty.jaccard_similarity(empty), 1.0);
+        assert_eq!(empty.dice_coefficient(empty), 1.0);
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn cosine_similarity() {
+        let empty = Bitmap::<200>::new();
+        let a = Bitmap::<200>::from_range(0..100);
+        let b = Bitmap::<200>::from_range(50..150);
+        assert_eq!(a.cosine_similarity(b), 0.5);
+        assert_eq!(a.cosine_similarity(a), 1.0);
+        assert_eq!(a.cosine_similarity(empty), 0.0);
+        assert_eq!(empty.cosine_similarity(empty), 1.0);
+    }
+
+    #[test]
+    fn iter_dense() {
+        let bitmap = Bitmap::<1024>::ones();
+        assert!(bitmap.into_iter().eq(0..1024));
+        assert!(bitmap.into_iter().rev().eq((0..1024).rev()));
+        let mut iter = bitmap.into_iter();
+        for index in 0..512 {
+            assert_eq!(iter.next(), Some(index));
+            assert_eq!(iter.next_back(), Some(1023 - index));
+        }
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let single = Bitmap::<1>::ones();
+        let mut iter = single.into_iter();
+        assert_eq!(iter.next_back(), Some(0));
+        assert_eq!(iter.next(), None);
+        assert!(Bitmap::<61>::ones().into_iter().eq(0..61));
+    }
+
+    #[test]
+    fn iter_double_ended() {
+        let mut bitmap = Bitmap::<300>::new();
+        for index in [1, 100, 200, 299] {
+            bitmap.set(index, true);
+        }
+        assert_eq!(
+            bitmap.into_iter().rev().collect::<Vec<_>>(),
+            vec![299, 200, 100, 1]
+        );
+
+        let mut iter = bitmap.into_iter();
+        assert_eq!(iter.size_hint(), (4, Some(4)));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(299));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next(), Some(100));
+        assert_eq!(iter.next_back(), Some(200));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = bitmap.into_iter();
+        assert_eq!(iter.next_back(), Some(299));
+        assert_eq!(iter.next_back(), Some(200));
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next(), Some(100));
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn subsets() {
+        let mut bitmap = Bitmap::<300>::new();
+        bitmap.set(3, true);
+        bitmap.set(130, true);
+        bitmap.set(299, true);
+        let subsets: Vec<Vec<usize>> = bitmap
+            .subsets()
+            .map(|subset| subset.into_iter().collect())
+            .collect();
+        assert_eq!(
+            subsets,
+            vec![
+                vec![],
+                vec![3],
+                vec![130],
+                vec![3, 130],
+                vec![299],
+                vec![3, 299],
+                vec![130, 299],
+                vec![3, 130, 299],
+            ]
+        );
+        assert_eq!(
+            Bitmap::<10>::new().subsets().collect::<Vec<_>>(),
+            vec![Bitmap::new()]
+        );
+        assert_eq!(Bitmap::<20>::from_value(0xfffff).subsets().count(), 1 << 20);
+    }
+
+    #[test]
+    fn combinations() {
+        assert_eq!(Bitmap::<10>::mask(3).combinations().count(), 120);
+        assert_eq!(Bitmap::<200>::mask(1).combinations().count(), 200);
+        assert_eq!(Bitmap::<200>::mask(2).combinations().count(), 199 * 100);
+        assert!(Bitmap::<200>::mask(2)
+            .combinations()
+            .all(|combination| combination.len() == 2));
+        assert_eq!(
+            Bitmap::<200>::mask(2).combinations().last(),
+            Some(Bitmap::from_range(198..200))
+        );
+        let start = Bitmap::<300>::from_range(126..129);
+        let mut combinations = start.combinations();
+        assert_eq!(combinations.next(), Some(start));
+        let mut expected = Bitmap::<300>::from_range(0..2);
+        expected.set(129, true);
+        assert_eq!(combinations.next(), Some(expected));
+        assert_eq!(
+            Bitmap::<10>::new().combinations().collect::<Vec<_>>(),
+            vec![Bitmap::new()]
+        );
+        assert_eq!(Bitmap::<10>::ones().combinations().count(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn window_counts_zero_width() {
+        let _ = Bitmap::<10>::new().window_counts(0);
+    }
+
+    #[test]
+    #[cfg(feature = "zerocopy")]
+    fn zerocopy() {
+        use ::zerocopy::{AsBytes, FromZeroes, Unaligned};
+
+        fn assert_unaligned<T: Unaligned>() {}
+        assert_unaligned::<Bitmap<1>>();
+        assert_unaligned::<Bitmap<8>>();
+
+        let bitmap = Bitmap::<16>::from_value(0x1234);
+        assert_eq!(AsBytes::as_bytes(&bitmap), &0x1234u16.to_ne_bytes());
+        assert_eq!(AsBytes::as_bytes(&Bitmap::<1>::ones()), &[1]);
+        assert_eq!(Bitmap::<300>::new_zeroed(), Bitmap::new());
+    }
+
+    #[test]
+    #[cfg(target_pointer_width = "64")]
+    fn shift_past_u32() {
+        let shift = (1usize << 32) + 1;
+        assert!((Bitmap::<64>::from_range(0..3) << shift).is_empty());
+        assert!((Bitmap::<64>::from_range(0..3) >> shift).is_empty());
+        assert!((Bitmap::<64>::from_range(0..3) << (1usize << 32)).is_empty());
+        assert!((Bitmap::<8>::ones() >> (1usize << 32)).is_empty());
+    }
+
+    #[test]
+    fn eq_and_hash() {
+        use std::collections::HashSet;
+
+        fn assert_eq_bound<T: Eq>() {}
+        assert_eq_bound::<Bitmap<1>>();
+        assert_eq_bound::<Bitmap<100>>();
+        assert_eq_bound::<Bitmap<1024>>();
+
+        let mut set = HashSet::new();
+        assert!(set.insert(Bitmap::<300>::from_range(0..10)));
+        assert!(set.insert(Bitmap::<300>::from_range(0..20)));
+        assert!(!set.insert(Bitmap::<300>::from_range(0..10)));
+        assert!(set.contains(&Bitmap::from_range(0..20)));
+        assert_eq!(set.len(), 2);
+    }
+
+    #[test]
+    fn ord() {
+        let mut low = Bitmap::<256>::new();
+        low.set(0, true);
+        let mut high = Bitmap::<256>::new();
+        high.set(128, true);
+        assert!(low < high);
+        assert!(Bitmap::<256>::new() < low);
+        assert_eq!(low.cmp(&low), core::cmp::Ordering::Equal);
+        assert!(Bitmap::<1>::new() < Bitmap::<1>::ones());
+
+        let mut sorted = vec![high, Bitmap::ones(), low, Bitmap::new()];
+        sorted.sort();
+        assert_eq!(sorted, vec![Bitmap::new(), low, high, Bitmap::ones()]);
+    }
+
+    #[test]
+    fn radix_formatting() {
+        let mut bitmap = Bitmap::<300>::new();
+        bitmap.set(0, true);
+        bitmap.set(299, true);
+        let binary = format!("{:b}", bitmap);
+        assert_eq!(binary.len(), 300);
+        assert_eq!(&binary[..2], "10");
+        assert_eq!(&binary[298..], "01");
+        assert_eq!(binary.matches('1').count(), 2);
+        let hex = format!("{:x}", bitmap);
+        assert_eq!(hex.len(), 75);
+        assert!(hex.starts_with("8000"));
+        assert!(hex.ends_with("0001"));
+        assert_eq!(format!("{:o}", bitmap).len(), 100);
+        assert!(format!("{:o}", bitmap).starts_with("4000"));
+
+        assert_eq!(format!("{:b}", Bitmap::<1>::ones()), "1");
+        assert_eq!(format!("{:x}", Bitmap::<8>::from_value(0xa5)), "a5");
+        assert_eq!(format!("{:X}", Bitmap::<8>::from_value(0xa5)), "A5");
+        assert_eq!(format!("{:#o}", Bitmap::<8>::from_value(0o377)), "0o377");
+        assert_eq!(format!("{:>6b}", Bitmap::<3>::from_value(0b101)), "   101");
+        assert_eq!(format!("{:#06b}", Bitmap::<3>::from_value(0b101)), "0b0101");
+    }
+
+    #[test]
+    fn display() {
+        let bitmap = Bitmap::<256>::from_range(0..2);
+        let string = bitmap.to_string();
+        assert_eq!(string.len(), 256);
+        assert!(string.ends_with("011"));
+        assert_eq!(string, format!("{:b}", bitmap));
+        assert_eq!(Bitmap::<1>::new().to_string(), "0");
+        assert_eq!(format!("{:#}", Bitmap::<3>::ones()), "111");
+    }
+
+    #[test]
+    fn from_str() {
+        assert_eq!("101".parse::<Bitmap<3>>(), Ok(Bitmap::from_value(0b101)));
+        assert_eq!("0b0101".parse::<Bitmap<3>>(), Ok(Bitmap::from_value(0b101)));
+        assert_eq!("0xA5".parse::<Bitmap<8>>(), Ok(Bitmap::from_value(0xa5)));
+        assert_eq!("1".parse::<Bitmap<1>>(), Ok(Bitmap::ones()));
+        assert_eq!("".parse::<Bitmap<8>>(), Err(Error::Empty));
+        assert_eq!("0x".parse::<Bitmap<8>>(), Err(Error::Empty));
+        assert_eq!("102".parse::<Bitmap<8>>(), Err(Error::InvalidDigit));
+        assert_eq!("0xfg".parse::<Bitmap<8>>(), Err(Error::InvalidDigit));
+        assert_eq!("-1".parse::<Bitmap<8>>(), Err(Error::InvalidDigit));
+        assert_eq!(
+            "1000".parse::<Bitmap<3>>(),
+            Err(Error::Overflow { size: 3 })
+        );
+        assert_eq!(
+            "0x10".parse::<Bitmap<3>>(),
+            Err(Error::Overflow { size: 3 })
+        );
+        assert_eq!("0x07".parse::<Bitmap<3>>(), Ok(Bitmap::ones()));
+
+        let mut bitmap = Bitmap::<300>::from_range(100..200);
+        bitmap.set(299, true);
+        assert_eq!(bitmap.to_string().parse(), Ok(bitmap));
+        assert_eq!(format!("{:#x}", bitmap).parse(), Ok(bitmap));
+        assert_eq!(format!("{:#b}", bitmap).parse(), Ok(bitmap));
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn encode_decode() {
+        let mut bitmap = Bitmap::<300>::from_range(100..200);
+        bitmap.set(299, true);
+        let mut out = vec![0xff];
+        bitmap.encode(&mut out);
+        assert_eq!(out.len(), 1 + Bitmap::<300>::ENCODED_LEN);
+        assert_eq!(out[1..6], [1, 44, 1, 0, 0]);
+        assert_eq!(out[out.len() - 1], 0b1000);
+        assert_eq!(Bitmap::decode(&out[1..]), Ok(bitmap));
+
+        let mut out = Vec::new();
+        Bitmap::<1>::ones().encode(&mut out);
+        assert_eq!(out, [1, 1, 0, 0, 0, 1]);
+        assert_eq!(Bitmap::decode(&out), Ok(Bitmap::<1>::ones()));
+
+        let invalid_length = |found| Error::InvalidLength { expected: 7, found };
+        assert_eq!(Bitmap::<12>::decode(&[]), Err(invalid_length(0)));
+        assert_eq!(Bitmap::<12>::decode(&[1, 12]), Err(invalid_length(2)));
+        assert_eq!(
+            Bitmap::<12>::decode(&[1, 12, 0, 0, 0, 0]),
+            Err(invalid_length(6))
+        );
+        assert_eq!(
+            Bitmap::<12>::decode(&[1, 12, 0, 0, 0, 0, 0, 0]),
+            Err(invalid_length(8))
+        );
+        assert_eq!(
+            Bitmap::<12>::decode(&[2, 12, 0, 0, 0, 0, 0]),
+            Err(Error::UnsupportedVersion { version: 2 })
+        );
+        assert_eq!(
+            Bitmap::<12>::decode(&[1, 16, 0, 0, 0, 0, 0]),
+            Err(Error::SizeMismatch {
+                expected: 12,
+                found: 16
+            })
+        );
+        assert_eq!(
+            Bitmap::<12>::decode(&[1, 12, 0, 0, 0, 0, 0b10000]),
+            Err(Error::Overflow { size: 12 })
+        );
+    }
+
+    #[test]
+    fn debug() {
+        let mut bitmap = Bitmap::<64>::new();
+        bitmap.set(3, true);
+        bitmap.set(5, true);
+        bitmap.set(8, true);
+        assert_eq!(format!("{:?}", bitmap), "Bitmap<64> {3, 5, 8}");
+        assert_eq!(format!("{:?}", Bitmap::<1024>::new()), "Bitmap<1024> {}");
+        assert_eq!(
+            format!("{:#?}", Bitmap::<300>::from_range(0..2)),
+            "Bitmap<300> {\n    0,\n    1,\n}"
+        );
+    }
+
+    #[test]
+    fn from_iter_indices() {
+        let bitmap: Bitmap<300> = (100..200).chain([299]).collect();
+        assert_eq!(bitmap.len(), 101);
+        assert!(bitmap.get(299));
+        assert_eq!(bitmap.into_iter().collect::<Bitmap<300>>(), bitmap);
+        assert_eq!(
+            core::iter::empty::<usize>().collect::<Bitmap<8>>(),
+            Bitmap::new()
+        );
+    }
+
+    #[test]
+    fn from_iter_bools() {
+        let values: Vec<bool> = (0..300).map(|index| index % 3 == 0).collect();
+        let bitmap: Bitmap<300> = values.iter().copied().collect();
+        assert_eq!(bitmap.len(), 100);
+        assert!(bitmap.bits().all(|(index, value)| value == values[index]));
+
+        let bitmap: Bitmap<4> = [true; 10].iter().copied().collect();
+        assert_eq!(bitmap, Bitmap::ones());
+
+        let mut bitmap = Bitmap::<4>::ones();
+        bitmap.extend([false, false]);
+        assert_eq!(bitmap.into_value(), 0b1100);
+    }
+
+    #[test]
+    fn from_bools() {
+        let bitmap = Bitmap::from([false, true, true, false]);
+        assert_eq!(bitmap.into_value(), 0b0110);
+        let mut values = [false; 300];
+        values[299] = true;
+        assert_eq!(Bitmap::from(values).first_index(), Some(299));
+
+        assert_eq!(
+            Bitmap::<300>::try_from(&values[..]),
+            Ok(Bitmap::from_range(299..300))
+        );
+        assert_eq!(
+            Bitmap::<300>::try_from(&values[1..]),
+            Err(Error::InvalidLength {
+                expected: 300,
+                found: 299
+            })
+        );
+        assert!(Bitmap::<1>::try_from(&[true, true][..]).is_err());
+    }
+
+    #[test]
+    fn sum_and_product() {
+        let bitmaps = [
+            Bitmap::<300>::from_range(0..200),
+            Bitmap::from_range(100..300),
+            Bitmap::from_range(150..250),
+        ];
+        assert_eq!(bitmaps.iter().copied().sum::<Bitmap<300>>(), Bitmap::ones());
+        assert_eq!(
+            bitmaps.iter().copied().collect::<Bitmap<300>>(),
+            Bitmap::ones()
+        );
+        assert_eq!(
+            bitmaps.iter().copied().product::<Bitmap<300>>(),
+            Bitmap::from_range(150..200)
+        );
+        assert_eq!(
+            core::iter::empty::<Bitmap<10>>().sum::<Bitmap<10>>(),
+            Bitmap::new()
+        );
+        assert_eq!(
+            core::iter::empty::<Bitmap<10>>().product::<Bitmap<10>>(),
+            Bitmap::ones()
+        );
+    }
+
+    #[test]
+    fn from_uint() {
+        assert_eq!(Bitmap::<8>::from(0xa5u8).into_value(), 0xa5);
+        assert_eq!(Bitmap::<10>::from(0xa5u8).into_value(), 0xa5);
+        let bitmap = Bitmap::<256>::from(0x8000_0001u32);
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 31]);
+        assert_eq!(Bitmap::<1024>::from(u128::MAX), Bitmap::mask(128));
+        assert_eq!(Bitmap::<64>::from(u64::MAX), Bitmap::ones());
+        assert_eq!(Bitmap::<100>::from(1u16 << 15).first_index(), Some(15));
+    }
+
+    #[test]
+    fn extend_indices() {
+        let mut bitmap = Bitmap::<300>::from_range(0..10);
+        bitmap.extend([5, 150, 299]);
+        bitmap.extend(core::iter::empty::<usize>());
+        assert_eq!(bitmap.len(), 12);
+        assert!(bitmap.get(150));
+        assert!(bitmap.get(299));
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_iter_out_of_bounds() {
+        let _: Bitmap<10> = [1, 10].iter().copied().collect();
+    }
+
+    #[test]
+    fn cursor() {
+        let mut bitmap = Bitmap::<300>::from_range(100..200);
+        bitmap.set(299, true);
+        let mut cursor = bitmap.cursor();
+        assert_eq!(cursor.peek(), Some(false));
+        assert_eq!(cursor.prev_one(), None);
+        assert_eq!(cursor.prev_zero(), None);
+        assert_eq!(cursor.next_zero(), Some(0));
+        assert_eq!(cursor.next_one(), Some(100));
+        assert_eq!(cursor.next_one(), Some(101));
+        assert_eq!(cursor.next_zero(), Some(200));
+        assert_eq!(cursor.next_one(), Some(299));
+        assert_eq!(cursor.position(), 300);
+        assert_eq!(cursor.peek(), None);
+        assert_eq!(cursor.next_one(), None);
+        assert_eq!(cursor.next_zero(), None);
+        assert_eq!(cursor.position(), 300);
+        assert_eq!(cursor.prev_one(), Some(299));
+        assert_eq!(cursor.prev_zero(), Some(298));
+        assert_eq!(cursor.prev_one(), Some(199));
+        assert_eq!(cursor.next_one(), Some(199));
+        cursor.seek(1000);
+        assert_eq!(cursor.position(), 300);
+        cursor.seek(150);
+        assert_eq!(cursor.peek(), Some(true));
+        assert_eq!(cursor.prev_zero(), Some(99));
+
+        let bitmap = Bitmap::<10>::ones();
+        let mut cursor = bitmap.cursor();
+        assert_eq!(cursor.next_zero(), None);
+        cursor.seek(10);
+        assert_eq!(cursor.prev_zero(), None);
+        assert_eq!(cursor.prev_one(), Some(9));
+    }
+
+    #[test]
+    fn drain() {
+        let mut bitmap = Bitmap::<300>::from_range(100..103);
+        bitmap.set(299, true);
+        assert_eq!(bitmap.drain().collect::<Vec<_>>(), vec![100, 101, 102, 299]);
+        assert!(bitmap.is_empty());
+
+        bitmap.set_range(10..20, true);
+        {
+            let mut drain = bitmap.drain();
+            assert_eq!(drain.len(), 10);
+            assert_eq!(drain.next_back(), Some(19));
+            assert_eq!(drain.next(), Some(10));
+            assert_eq!(drain.len(), 8);
+        }
+        assert!(bitmap.is_empty());
+        assert_eq!(bitmap.drain().next(), None);
+    }
+
+    #[test]
+    fn ranges() {
+        let mut bitmap = Bitmap::<300>::from_range(100..200);
+        bitmap.set(0, true);
+        bitmap.set(299, true);
+        assert_eq!(
+            bitmap.ranges().collect::<Vec<_>>(),
+            vec![0..1, 100..200, 299..300]
+        );
+        assert_eq!(
+            bitmap.ranges().rev().collect::<Vec<_>>(),
+            vec![299..300, 100..200, 0..1]
+        );
+        assert_eq!(
+            bitmap.zero_ranges().collect::<Vec<_>>(),
+            vec![1..100, 200..299]
+        );
+        assert_eq!(
+            Bitmap::<10>::ones().ranges().collect::<Vec<_>>(),
+            vec![0..10]
+        );
+        assert_eq!(
+            Bitmap::<10>::ones().zero_ranges().rev().collect::<Vec<_>>(),
+            vec![]
+        );
+        assert_eq!(
+            Bitmap::<1>::new().zero_ranges().collect::<Vec<_>>(),
+            vec![0..1]
+        );
+    }
+
+    #[test]
+    fn iter_words() {
+        let bitmap = Bitmap::<1024>::from_range(0..1024);
+        assert_eq!(bitmap.iter_words().len(), 8);
+        assert!(bitmap.iter_words().all(|(_, word)| word == u128::MAX));
+        assert_eq!(
+            bitmap.iter_words().next_back().map(|(index, _)| index),
+            Some(7)
+        );
+        assert_eq!(
+            Bitmap::<1>::ones().iter_words().collect::<Vec<_>>(),
+            vec![(0, 1)]
+        );
+        assert_eq!(
+            Bitmap::<100>::from_range(98..100)
+                .iter_words()
+                .collect::<Vec<_>>(),
+            vec![(0, 0b11 << 98)]
+        );
+    }
+
+    #[test]
+    fn chunks() {
+        let mut bitmap = Bitmap::<1024>::new();
+        for index in [0, 63, 64, 700, 1023] {
+            bitmap.set(index, true);
+        }
+        let chunks: Vec<Bitmap<64>> = bitmap.chunks::<64>().collect();
+        assert_eq!(chunks.len(), 16);
+        assert_eq!(chunks[0].into_value(), 1 | 1 << 63);
+        assert_eq!(chunks[1].into_value(), 1);
+        assert_eq!(chunks[10].into_value(), 1 << (700 - 640));
+        assert_eq!(chunks[15].into_value(), 1 << 63);
+
+        let chunks: Vec<Bitmap<300>> = bitmap.chunks::<300>().collect();
+        assert_eq!(chunks.len(), 4);
+        assert_eq!(chunks[0].into_iter().collect::<Vec<_>>(), vec![0, 63, 64]);
+        assert_eq!(chunks[2].into_iter().collect::<Vec<_>>(), vec![100]);
+        assert_eq!(chunks[3].into_iter().collect::<Vec<_>>(), vec![123]);
+
+        let bitmap = Bitmap::<10>::ones();
+        let chunks: Vec<Bitmap<1>> = bitmap.chunks::<1>().collect();
+        assert_eq!(chunks, vec![Bitmap::ones(); 10]);
+        assert_eq!(bitmap.chunks::<64>().next(), Some(Bitmap::mask(10)));
+    }
+
+    #[test]
+    fn bits() {
+        let bitmap = Bitmap::<300>::from_range(100..200);
+        let mut bits = bitmap.bits();
+        assert_eq!(bits.len(), 300);
+        assert_eq!(bits.next(), Some((0, false)));
+        assert_eq!(bits.next_back(), Some((299, false)));
+        assert!(bitmap
+            .bits()
+            .all(|(index, value)| value == (100..200).contains(&index)));
+        assert_eq!(
+            Bitmap::<1>::ones().bits().collect::<Vec<_>>(),
+            vec![(0, true)]
+        );
+    }
+
+    #[test]
+    fn iter_range() {
+        let mut bitmap = Bitmap::<300>::new();
+        for index in [0, 5, 127, 128, 250, 299] {
+            bitmap.set(index, true);
+        }
+        assert_eq!(
+            bitmap.iter_from(6).collect::<Vec<_>>(),
+            vec![127, 128, 250, 299]
+        );
+        assert_eq!(bitmap.iter_from(299).collect::<Vec<_>>(), vec![299]);
+        assert_eq!(bitmap.iter_from(300).next(), None);
+        assert_eq!(bitmap.iter_from(1000).next(), None);
+        assert_eq!(bitmap.iter_range(5..128).collect::<Vec<_>>(), vec![5, 127]);
+        assert_eq!(
+            bitmap.iter_range(..=5).rev().collect::<Vec<_>>(),
+            vec![5, 0]
+        );
+        assert_eq!(bitmap.iter_range(128..128).next(), None);
+        assert_eq!(bitmap.iter_range(200..1000).len(), 2);
+    }
+
+    #[test]
+    fn iter_zeros() {
+        let mut bitmap = Bitmap::<300>::ones();
+        bitmap.set(0, false);
+        bitmap.set(200, false);
+        let mut iter = bitmap.iter_zeros();
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(200));
+        assert_eq!(iter.next(), Some(0));
+        assert_eq!(iter.next(), None);
+
+        let bitmap = Bitmap::<10>::from_range(0..8);
+        assert_eq!(bitmap.iter_zeros().collect::<Vec<_>>(), vec![8, 9]);
+        assert_eq!(Bitmap::<1>::new().iter_zeros().collect::<Vec<_>>(), vec![0]);
+    }
+
+    #[test]
+    fn iter_fused_and_cloned() {
+        let bitmap = Bitmap::<10>::from_range(3..5);
+        let mut iter = bitmap.into_iter();
+        assert_eq!(iter.next(), Some(3));
+        let copy = iter.clone();
+        assert_eq!(iter.next(), Some(4));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(copy.collect::<Vec<_>>(), vec![4]);
+        assert!(format!("{:?}", iter).starts_with("Iter"));
+    }
+
+    #[test]
+    fn weighted_sum() {
+        let weights: Vec<u64> = (0..300).collect();
+        let bitmap = Bitmap::<300>::from_range(100..200);
+        assert_eq!(bitmap.weighted_sum(&weights), (100..200).sum::<u64>());
+        assert_eq!(Bitmap::<300>::new().weighted_sum(&weights), 0);
+        assert_eq!(Bitmap::<1>::ones().weighted_sum(&[7]), 7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn weighted_sum_short_weights() {
+        Bitmap::<10>::new().weighted_sum(&[1; 9]);
+    }
+
+    #[test]
+    fn next_index_wrapping() {
+        let mut bitmap = Bitmap::<300>::new();
+        assert_eq!(bitmap.next_index_wrapping(0), None);
+        bitmap.set(150, true);
+        assert_eq!(bitmap.next_index_wrapping(150), Some(150));
+        assert_eq!(bitmap.next_index_wrapping(299), Some(150));
+        bitmap.set(3, true);
+        assert_eq!(bitmap.next_index_wrapping(3), Some(150));
+        assert_eq!(bitmap.next_index_wrapping(150), Some(3));
+        assert_eq!(bitmap.next_index_wrapping(500), Some(3));
+
+        let bitmap = Bitmap::<1>::ones();
+        assert_eq!(bitmap.next_index_wrapping(0), Some(0));
+    }
+
+    #[test]
+    fn count_zeros() {
+        assert_eq!(Bitmap::<1>::new().count_zeros(), 1);
+        assert_eq!(Bitmap::<1>::ones().count_zeros(), 0);
+        assert_eq!(Bitmap::<10>::from_range(2..5).count_zeros(), 7);
+        assert_eq!(Bitmap::<10>::ones().count_zeros(), 0);
+        assert_eq!(Bitmap::<300>::from_range(100..200).count_zeros(), 200);
+    }
+
+    #[test]
+    fn combine_with() {
+        let mut bitmap = Bitmap::<10>::from_range(0..6);
+        bitmap.combine_with(&Bitmap::from_range(3..9), |left, right| left & !right);
+        assert_eq!(bitmap, Bitmap::from_range(0..3));
+        bitmap.combine_with(&Bitmap::new(), |_, _| u128::MAX);
+        assert!(bitmap.is_full());
+        assert_eq!(bitmap.len(), 10);
+
+        let mut bitmap = Bitmap::<1>::new();
+        bitmap.combine_with(&Bitmap::ones(), |left, right| left | right);
+        assert!(bitmap.is_full());
+
+        let mut bitmap = Bitmap::<300>::new();
+        bitmap.combine_with(&Bitmap::new(), |_, _| u128::MAX);
+        assert_eq!(bitmap, Bitmap::ones());
+        assert_eq!(bitmap.len(), 300);
+    }
+
+    #[test]
+    fn as_words() {
+        let mut bitmap = Bitmap::<10>::new();
+        bitmap.set(9, true);
+        assert_eq!(bitmap.as_words(), &[0b10_0000_0000]);
+        bitmap.as_words_mut()[0] = 0b11;
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 1]);
+
+        let mut bitmap = Bitmap::<1>::new();
+        bitmap.as_words_mut()[0] = true;
+        assert_eq!(bitmap.as_words(), &[true]);
+
+        let mut bitmap = Bitmap::<1024>::new();
+        assert_eq!(bitmap.as_words().len(), 8);
+        bitmap.as_words_mut()[7] = 1 << 127;
+        assert_eq!(bitmap.first_index(), Some(1023));
+    }
+
+    #[test]
+    fn try_from_bytes() {
+        let bitmap: Bitmap<12> = TryFrom::try_from(&[0b1, 0b10][..]).unwrap();
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 9]);
+        assert_eq!(
+            Bitmap::<12>::try_from(&[0b1][..]),
+            Err(Error::InvalidLength {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            Bitmap::<12>::try_from(&[0, 0, 0][..]),
+            Err(Error::InvalidLength {
+                expected: 2,
+                found: 3
+            })
+        );
+        assert_eq!(
+            Bitmap::<12>::try_from(&[0, 0b10000][..]),
+            Err(Error::Overflow { size: 12 })
+        );
+        assert!(Bitmap::<1>::try_from(&[1][..]).unwrap().get(0));
+        assert_eq!(
+            Bitmap::<1>::try_from(&[2][..]),
+            Err(Error::Overflow { size: 1 })
+        );
+        let bitmap = Bitmap::<300>::from_range(5..290);
+        assert_eq!(
+            Bitmap::<300>::try_from(bitmap.to_le_bytes().as_ref()),
+            Ok(bitmap)
+        );
+        let mut bytes = Bitmap::<300>::ones().to_le_bytes();
+        bytes[47] = 0xff;
+        assert!(Bitmap::<300>::try_from(&bytes[..]).is_err());
+    }
+
+    #[test]
+    fn endian_bytes() {
+        let mut bitmap = Bitmap::<300>::new();
+        bitmap.set(0, true);
+        bitmap.set(130, true);
+        bitmap.set(299, true);
+        let le = bitmap.to_le_bytes();
+        let be = bitmap.to_be_bytes();
+        assert_eq!(le.len(), 48);
+        assert_eq!(le[0], 1);
+        assert_eq!(le[16], 0b100);
+        assert_eq!(le[37], 0b1000);
+        let mut reversed = le;
+        reversed.reverse();
+        assert_eq!(reversed, be);
+        assert_eq!(Bitmap::<300>::from_le_bytes(le), bitmap);
+        assert_eq!(Bitmap::<300>::from_be_bytes(be), bitmap);
+        assert_eq!(Bitmap::<300>::from_le_bytes([0xff; 48]), Bitmap::ones());
+
+        assert_eq!(Bitmap::<1>::from_le_bytes([1]).to_be_bytes(), [1]);
+        assert_eq!(Bitmap::<3>::from_be_bytes([0xff]).len(), 3);
+        assert_eq!(Bitmap::<64>::ones().to_le_bytes(), [0xff; 8]);
+    }
+
+    #[test]
+    fn layout_and_pointers() {
+        assert_eq!(size_of::<Bitmap<1>>(), size_of::<bool>());
+        assert_eq!(size_of::<Bitmap<10>>(), size_of::<u16>());
+        assert_eq!(size_of::<Bitmap<300>>(), size_of::<[u128; 3]>());
+        assert_eq!(
+            core::mem::align_of::<Bitmap<300>>(),
+            core::mem::align_of::<[u128; 3]>()
+        );
+
+        let mut bitmap = Bitmap::<300>::new();
+        assert_eq!(bitmap.as_ptr(), bitmap.as_bytes().as_ptr());
+        unsafe { *bitmap.as_mut_ptr() = 0b10 };
+        assert_eq!(bitmap.first_index(), Some(1));
+    }
+
+    #[test]
+    fn as_bytes_mut() {
+        let mut bitmap = Bitmap::<300>::new();
+        assert_eq!(bitmap.as_bytes().len(), 48);
+        bitmap.as_bytes_mut().fill(0xff);
+        bitmap &= Bitmap::mask(300);
+        assert!(bitmap.is_full());
+        bitmap.as_bytes_mut().fill(0);
+        assert!(bitmap.is_empty());
+
+        let mut bitmap = Bitmap::<16>::new();
+        bitmap
+            .as_bytes_mut()
+            .copy_from_slice(&0x8001u16.to_ne_bytes());
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 15]);
+    }
+
+    #[test]
+    fn as_value_mut() {
+        let mut bitmap = Bitmap::<10>::new();
+        *bitmap.as_value_mut() |= 0b101;
+        assert_eq!(*bitmap.as_value(), 0b101);
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 2]);
+
+        let mut bitmap = Bitmap::<256>::new();
+        bitmap.as_value_mut()[1] = 1;
+        assert_eq!(bitmap.first_index(), Some(128));
+    }
+
+    #[test]
+    fn length_and_capacity() {
+        assert_eq!(Bitmap::<1>::LENGTH, 1);
+        assert_eq!(Bitmap::<300>::LENGTH, 300);
+        assert_eq!(Bitmap::<300>::new().capacity(), 300);
+        assert_eq!(Bitmap::<300>::ones().capacity(), 300);
+    }
+
+    #[test]
+    fn bit_ref() {
+        let mut bitmap = Bitmap::<300>::new();
+        for index in [2, 130, 299] {
+            let mut bit = bitmap.bit_mut(index);
+            assert_eq!(bit.index(), index);
+            assert!(!bit.get());
+            bit.set(true);
+            assert!(bit.get());
+            assert!(bit.replace(false));
+            assert!(!bit.toggle());
+        }
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![2, 130, 299]);
+    }
+
+    #[test]
+    fn index() {
+        let mut bitmap = Bitmap::<200>::new();
+        bitmap.set(5, true);
+        bitmap.set(150, true);
+        assert!(bitmap[5]);
+        assert!(bitmap[150]);
+        assert!(!bitmap[6]);
+        assert!(!bitmap[199]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds() {
+        let bitmap = Bitmap::<10>::new();
+        let _ = bitmap[10];
+    }
+
+    #[test]
+    fn get_and_set_unchecked() {
+        let mut bitmap = Bitmap::<300>::new();
+        for index in [0, 1, 127, 128, 255, 256, 299] {
+            assert!(!unsafe { bitmap.set_unchecked(index, true) });
+            assert!(unsafe { bitmap.get_unchecked(index) });
+            assert!(bitmap.get(index));
+        }
+        assert_eq!(bitmap.len(), 7);
+        assert!(unsafe { bitmap.set_unchecked(128, false) });
+        assert!(!unsafe { bitmap.get_unchecked(128) });
+
+        let mut bitmap = Bitmap::<1>::new();
+        assert!(!unsafe { bitmap.set_unchecked(0, true) });
+        assert!(unsafe { bitmap.get_unchecked(0) });
+    }
+
+    #[test]
+    fn try_get_and_set() {
+        let mut bitmap = Bitmap::<10>::new();
+        assert_eq!(bitmap.try_set(3, true), Ok(false));
+        assert_eq!(bitmap.try_set(3, true), Ok(true));
+        assert_eq!(bitmap.try_get(3), Some(true));
+        assert_eq!(bitmap.try_get(4), Some(false));
+        assert_eq!(bitmap.try_get(10), None);
+        assert_eq!(
+            bitmap.try_set(10, true),
+            Err(Error::OutOfBounds {
+                index: 10,
+                size: 10
+            })
+        );
+        assert_eq!(bitmap.len(), 1);
+    }
+
+    #[test]
+    fn clear() {
+        let mut bitmap = !Bitmap::<200>::new();
+        bitmap.clear();
+        assert!(bitmap.is_empty());
+        assert_eq!(bitmap, Bitmap::new());
+    }
+
+    #[test]
+    fn set_range_bounds() {
+        let mut bitmap = Bitmap::<10>::new();
+        bitmap.set_range(7.., true);
+        bitmap.set_range(..=2, true);
+        assert!(bitmap.into_iter().eq([0, 1, 2, 7, 8, 9]));
+        bitmap.set_range(.., false);
+        assert!(bitmap.is_empty());
+    }
+
+    #[test]
+    fn ones() {
+        assert!(Bitmap::<1>::ones().is_full());
+        assert_eq!(Bitmap::<10>::ones().len(), 10);
+        assert_eq!(Bitmap::<64>::ones().len(), 64);
+        assert_eq!(Bitmap::<200>::ones().len(), 200);
+        assert_eq!(Bitmap::<1024>::ones(), !Bitmap::<1024>::new());
+    }
+
+    #[test]
+    #[should_panic]
+    fn mask_too_large() {
+        let _ = Bitmap::<10>::mask(11);
+    }
+
+    #[test]
+    #[should_panic]
+    fn from_range_too_large() {
+        let _ = Bitmap::<200>::from_range(150..201);
+    }
+
+    #[test]
+    fn fill() {
+        let mut bitmap = Bitmap::<10>::new();
+        bitmap.fill(true);
+        assert_eq!(bitmap.len(), 10);
+        assert!(bitmap.is_full());
+        bitmap.fill(false);
+        assert!(bitmap.is_empty());
+
+        let mut bitmap = Bitmap::<200>::new();
+        bitmap.set(5, true);
+        bitmap.fill(true);
+        assert_eq!(bitmap.len(), 200);
+        bitmap.fill(false);
+        assert!(bitmap.is_empty());
+    }
+
+    proptest! {
+        #[test]
+        fn get_set_and_iter_61(bits in btree_set(0..61usize, 0..61)) {
+            let mut bitmap = Bitmap::<61>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            for i in 0..61 {
+                assert_eq!(bitmap.get(i), bits.contains(&i));
+            }
+            assert_eq!(bitmap.first_index(), bits.clone().into_iter().next());
+            assert_eq!(bitmap.last_index(), bits.clone().into_iter().next_back());
+            assert!(bitmap.into_iter().eq(bits.clone().into_iter()));
+            assert!(bitmap.into_iter().rev().eq(bits.into_iter().rev()));
+        }
+
+        #[test]
+        fn get_set_and_iter_64(bits in btree_set(0..64usize, 0..64)) {
+            let mut bitmap = Bitmap::<64>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            for i in 0..64 {
+                assert_eq!(bitmap.get(i), bits.contains(&i));
+            }
+            assert_eq!(bitmap.first_index(), bits.clone().into_iter().next());
+            assert_eq!(bitmap.last_index(), bits.clone().into_iter().next_back());
+            assert!(bitmap.into_iter().eq(bits.clone().into_iter()));
+            assert!(bitmap.into_iter().rev().eq(bits.into_iter().rev()));
+        }
+
+        #[test]
+        fn get_set_and_iter_1024(bits in btree_set(0..1024usize, 0..1024)) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            for i in 0..1024 {
+                assert_eq!(bitmap.get(i), bits.contains(&i));
+            }
+            assert_eq!(bitmap.first_index(), bits.clone().into_iter().next());
+            assert_eq!(bitmap.last_index(), bits.clone().into_iter().next_back());
+            assert!(bitmap.into_iter().eq(bits.clone().into_iter()));
+            assert!(bitmap.into_iter().rev().eq(bits.into_iter().rev()));
+        }
+
+        #[test]
+        fn toggle_200(bits in btree_set(0..200usize, 0..200), toggled in btree_set(0..200usize, 0..200)) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            for i in &toggled {
+                assert_eq!(bitmap.toggle(*i), bits.contains(i));
+            }
+            assert!(bitmap.into_iter().eq(bits.symmetric_difference(&toggled).cloned()));
+        }
+
+        #[test]
+        fn first_and_last_index_200(bits in btree_set(0..200usize, 0..200)) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.first_index(), bits.iter().next().cloned());
+            assert_eq!(bitmap.last_index(), bits.iter().next_back().cloned());
+        }
+
+        #[test]
+        fn next_index_200(bits in btree_set(0..200usize, 0..200), after in 0..210usize) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.next_index(after), bits.range((after + 1)..).next().cloned());
+        }
+
+        #[test]
+        fn next_index_61(bits in btree_set(0..61usize, 0..61), after in 0..70usize) {
+            let mut bitmap = Bitmap::<61>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.next_index(after), bits.range((after + 1)..).next().cloned());
+        }
+
+        #[test]
+        fn prev_index_256(bits in btree_set(0..256usize, 0..256), before in 0..300usize) {
+            let mut bitmap = Bitmap::<256>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.prev_index(before), bits.range(..before).next_back().cloned());
+        }
+
+        #[test]
+        fn prev_index_64(bits in btree_set(0..64usize, 0..64), before in 0..70usize) {
+            let mut bitmap = Bitmap::<64>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.prev_index(before), bits.range(..before).next_back().cloned());
+        }
+
+        #[test]
+        fn first_false_index_10(bits in btree_set(0..10usize, 0..=10)) {
+            let mut bitmap = Bitmap::<10>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.first_false_index(), (0..10).find(|i| !bits.contains(i)));
+        }
+
+        #[test]
+        fn first_false_index_200(cleared in btree_set(0..200usize, 0..4)) {
+            let mut bitmap = !Bitmap::<200>::new();
+            for i in &cleared {
+                bitmap.set(*i, false);
+            }
+            assert_eq!(bitmap.first_false_index(), cleared.iter().next().cloned());
+        }
+
+        #[test]
+        fn false_index_10(cleared in btree_set(0..10usize, 0..=10), index in 0..12usize) {
+            let mut bitmap = !Bitmap::<10>::new();
+            for i in &cleared {
+                bitmap.set(*i, false);
+            }
+            assert_eq!(bitmap.last_false_index(), cleared.iter().next_back().cloned());
+            assert_eq!(bitmap.next_false_index(index), cleared.range((index + 1)..).next().cloned());
+            assert_eq!(bitmap.prev_false_index(index), cleared.range(..index).next_back().cloned());
+        }
+
+        #[test]
+        fn false_index_200(cleared in btree_set(0..200usize, 0..8), index in 0..210usize) {
+            let mut bitmap = !Bitmap::<200>::new();
+            for i in &cleared {
+                bitmap.set(*i, false);
+            }
+            assert_eq!(bitmap.last_false_index(), cleared.iter().next_back().cloned());
+            assert_eq!(bitmap.next_false_index(index), cleared.range((index + 1)..).next().cloned());
+            assert_eq!(bitmap.prev_false_index(index), cleared.range(..index).next_back().cloned());
+        }
+
+        #[test]
+        fn mask(bits in 0..=8usize, big_bits in 0..=1024usize) {
+            let bitmap = Bitmap::<8>::mask(bits);
+            assert!(bitmap.into_iter().eq(0..bits));
+            let bitmap = Bitmap::<128>::mask(bits * 16);
+            assert!(bitmap.into_iter().eq(0..bits * 16));
+            let bitmap = Bitmap::<1024>::mask(big_bits);
+            assert!(bitmap.into_iter().eq(0..big_bits));
+        }
+
+        #[test]
+        fn from_range(start in 0..=1024usize, end in 0..=1024usize) {
+            let bitmap = Bitmap::<1024>::from_range(start..end);
+            assert!(bitmap.into_iter().eq(start..end));
+            let bitmap = Bitmap::<64>::from_range(start / 16..end / 16);
+            assert!(bitmap.into_iter().eq(start / 16..end / 16));
+        }
+
+        #[test]
+        fn set_range(bits in btree_set(0..1024usize, 0..1024), start in 0..=1024usize, end in 0..=1024usize, value: bool) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            bitmap.set_range(start..end, value);
+            for i in 0..1024 {
+                let expected = if (start..end).contains(&i) { value } else { bits.contains(&i) };
+                assert_eq!(bitmap.get(i), expected);
+            }
+        }
+
+        #[test]
+        fn invert_range(bits in btree_set(0..200usize, 0..200), start in 0..=200usize, end in 0..=200usize) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            bitmap.invert_range(start..end);
+            for i in 0..200 {
+                assert_eq!(bitmap.get(i), bits.contains(&i) != (start..end).contains(&i));
+            }
+        }
+
+        #[test]
+        fn count_in_range(bits in btree_set(0..1024usize, 0..1024), start in 0..=1024usize, end in 0..=1024usize) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let expected = if start < end { bits.range(start..end).count() } else { 0 };
+            assert_eq!(bitmap.count_in_range(start..end), expected);
+        }
+
+        #[test]
+        fn get_bits_512(bits in btree_set(0..512usize, 0..512), start in 0..512usize, len in 0..=128usize) {
+            let mut bitmap = Bitmap::<512>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let end = (start + len).min(512);
+            let expected = bits.range(start..end).fold(0u128, |acc, i| acc | 1 << (i - start));
+            assert_eq!(bitmap.get_bits(start..end), expected);
+        }
+
+        #[test]
+        fn get_bits_64(bits in btree_set(0..64usize, 0..64), start in 0..64usize, len in 0..=64usize) {
+            let mut bitmap = Bitmap::<64>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let end = (start + len).min(64);
+            let expected = bits.range(start..end).fold(0u128, |acc, i| acc | 1 << (i - start));
+            assert_eq!(bitmap.get_bits(start..end), expected);
+        }
+
+        #[test]
+        fn set_bits_512(bits in btree_set(0..512usize, 0..512), start in 0..512usize, len in 0..=128usize, value: u128) {
+            let mut bitmap = Bitmap::<512>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let end = (start + len).min(512);
+            bitmap.set_bits(start..end, value);
+            for i in 0..512 {
+                let expected = if (start..end).contains(&i) { value & (1 << (i - start)) != 0 } else { bits.contains(&i) };
+                assert_eq!(bitmap.get(i), expected);
+            }
+        }
+
+        #[test]
+        fn set_bits_61(bits in btree_set(0..61usize, 0..61), start in 0..61usize, len in 0..=61usize, value: u128) {
+            let mut bitmap = Bitmap::<61>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let end = (start + len).min(61);
+            bitmap.set_bits(start..end, value);
+            for i in 0..61 {
+                let expected = if (start..end).contains(&i) { value & (1 << (i - start)) != 0 } else { bits.contains(&i) };
+                assert_eq!(bitmap.get(i), expected);
+            }
+            assert_eq!(bitmap.get_bits(start..end), value & Bitmap::<128>::mask(end - start).into_value());
+        }
+
+        #[test]
+        fn all_in_range(cleared in btree_set(0..200usize, 0..4), start in 0..=200usize, end in 0..=200usize) {
+            let mut bitmap = Bitmap::<200>::ones();
+            for i in &cleared {
+                bitmap.set(*i, false);
+            }
+            let expected = start >= end || cleared.range(start..end).next().is_none();
+            assert_eq!(bitmap.all_in_range(start..end), expected);
+        }
+
+        #[test]
+        fn any_in_range(bits in btree_set(0..200usize, 0..4), start in 0..=200usize, end in 0..=200usize) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let expected = start < end && bits.range(start..end).next().is_some();
+            assert_eq!(bitmap.any_in_range(start..end), expected);
+        }
+
+        #[test]
+        fn reverse_200(bits in btree_set(0..200usize, 0..200)) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let reversed = bitmap.reversed();
+            assert!(reversed.into_iter().eq(bits.iter().rev().map(|i| 199 - i)));
+            bitmap.reverse();
+            assert_eq!(bitmap, reversed);
+        }
+
+        #[test]
+        fn reverse_61(bits in btree_set(0..61usize, 0..61)) {
+            let mut bitmap = Bitmap::<61>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert!(bitmap.reversed().into_iter().eq(bits.iter().rev().map(|i| 60 - i)));
+            assert_eq!(bitmap.reversed().reversed(), bitmap);
+        }
+
+        #[test]
+        fn shift_200(bits in btree_set(0..200usize, 0..200), shift in 0..250usize) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
             }
+            assert!((bitmap << shift).into_iter().eq(bits.iter().map(|i| i + shift).filter(|i| *i < 200)));
+            assert!((bitmap >> shift).into_iter().eq(bits.iter().filter(|i| **i >= shift).map(|i| i - shift)));
         }
 
-        if let Some(index) = result {
-            if let Some(head) = self.head {
-                if head > index {
-                    self.head = Some(SIZE + 1);
-                    self.tail = None;
-                    return None;
-                }
+        #[test]
+        fn shift_10(bits in btree_set(0..10usize, 0..10), shift in 0..20usize) {
+            let mut bitmap = Bitmap::<10>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
             }
-
-            self.tail = Some(index);
-        } else {
-            self.tail = None;
+            let mut shifted = bitmap;
+            shifted <<= shift;
+            assert!(shifted.into_iter().eq(bits.iter().map(|i| i + shift).filter(|i| *i < 10)));
+            let mut shifted = bitmap;
+            shifted >>= shift;
+            assert!(shifted.into_iter().eq(bits.iter().filter(|i| **i >= shift).map(|i| i - shift)));
         }
 
-        result
-    }
-}
-
-#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
-#[allow(clippy::cast_ptr_alignment)]
-mod x86_arch {
-    use super::*;
-    #[cfg(target_arch = "x86")]
-    use core::arch::x86::*;
-    #[cfg(target_arch = "x86_64")]
-    use core::arch::x86_64::*;
+        #[test]
+        fn shift_carry_100(bits in btree_set(0..200usize, 0..200), shift in 0..=200usize) {
+            let mut whole = Bitmap::<200>::new();
+            let mut low = Bitmap::<100>::new();
+            let mut high = Bitmap::<100>::new();
+            for i in &bits {
+                whole.set(*i, true);
+                if *i < 100 {
+                    low.set(*i, true);
+                } else {
+                    high.set(*i - 100, true);
+                }
+            }
 
-    impl Bitmap<128> {
-        #[target_feature(enable = "sse2")]
-        pub unsafe fn load_m128i(&self) -> __m128i {
-            _mm_loadu_si128(&self.data as *const _ as *const __m128i)
-        }
-    }
+            let (mut shl_low, mut shl_high) = (low, high);
+            let carry = shl_low.shl_carry(shift);
+            shl_high.shl_carry(shift);
+            shl_high |= carry;
+            assert!(shl_low.into_iter().chain(shl_high.into_iter().map(|i| i + 100)).eq(&(whole << shift)));
 
-    impl Bitmap<256> {
-        #[target_feature(enable = "sse2")]
-        pub unsafe fn load_m128i(&self) -> [__m128i; 2] {
-            let ptr = &self.data as *const _ as *const __m128i;
-            [_mm_loadu_si128(ptr), _mm_loadu_si128(ptr.add(1))]
+            let (mut shr_low, mut shr_high) = (low, high);
+            let carry = shr_high.shr_carry(shift);
+            shr_low.shr_carry(shift);
+            shr_low |= carry;
+            assert!(shr_low.into_iter().chain(shr_high.into_iter().map(|i| i + 100)).eq(&(whole >> shift)));
         }
 
-        #[target_feature(enable = "avx")]
-        pub unsafe fn load_m256i(&self) -> __m256i {
-            _mm256_loadu_si256(&self.data as *const _ as *const __m256i)
+        #[test]
+        fn leading_and_trailing_10(bits in btree_set(0..10usize, 0..=10)) {
+            let mut bitmap = Bitmap::<10>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.trailing_zeros(), (0..10).take_while(|i| !bits.contains(i)).count());
+            assert_eq!(bitmap.trailing_ones(), (0..10).take_while(|i| bits.contains(i)).count());
+            assert_eq!(bitmap.leading_zeros(), (0..10).rev().take_while(|i| !bits.contains(i)).count());
+            assert_eq!(bitmap.leading_ones(), (0..10).rev().take_while(|i| bits.contains(i)).count());
         }
-    }
 
-    impl Bitmap<512> {
-        #[target_feature(enable = "sse2")]
-        pub unsafe fn load_m128i(&self) -> [__m128i; 4] {
-            let ptr = &self.data as *const _ as *const __m128i;
-            [
-                _mm_loadu_si128(ptr),
-                _mm_loadu_si128(ptr.add(1)),
-                _mm_loadu_si128(ptr.add(2)),
-                _mm_loadu_si128(ptr.add(3)),
-            ]
+        #[test]
+        fn leading_and_trailing_200(cleared in btree_set(0..200usize, 0..4), set in btree_set(0..200usize, 0..4)) {
+            let mut ones = Bitmap::<200>::ones();
+            for i in &cleared {
+                ones.set(*i, false);
+            }
+            let mut zeros = Bitmap::<200>::new();
+            for i in &set {
+                zeros.set(*i, true);
+            }
+            assert_eq!(ones.trailing_ones(), cleared.iter().next().cloned().unwrap_or(200));
+            assert_eq!(ones.leading_ones(), cleared.iter().next_back().map_or(200, |i| 199 - i));
+            assert_eq!(zeros.trailing_zeros(), set.iter().next().cloned().unwrap_or(200));
+            assert_eq!(zeros.leading_zeros(), set.iter().next_back().map_or(200, |i| 199 - i));
         }
 
-        #[target_feature(enable = "avx")]
-        pub unsafe fn load_m256i(&self) -> [__m256i; 2] {
-            let ptr = &self.data as *const _ as *const __m256i;
-            [_mm256_loadu_si256(ptr), _mm256_loadu_si256(ptr.add(1))]
+        #[test]
+        fn parity_1024(bits in btree_set(0..1024usize, 0..1024)) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.parity(), bitmap.into_iter().fold(false, |acc, _| !acc));
         }
-    }
 
-    impl Bitmap<768> {
-        #[target_feature(enable = "sse2")]
-        pub unsafe fn load_m128i(&self) -> [__m128i; 6] {
-            let ptr = &self.data as *const _ as *const __m128i;
-            [
-                _mm_loadu_si128(ptr),
-                _mm_loadu_si128(ptr.add(1)),
-                _mm_loadu_si128(ptr.add(2)),
-                _mm_loadu_si128(ptr.add(3)),
-                _mm_loadu_si128(ptr.add(4)),
-                _mm_loadu_si128(ptr.add(5)),
-            ]
+        #[test]
+        fn is_single_bit_1024(bits in btree_set(0..1024usize, 0..3)) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.is_single_bit(), bits.len() == 1);
         }
 
-        #[target_feature(enable = "avx")]
-        pub unsafe fn load_m256i(&self) -> [__m256i; 3] {
-            let ptr = &self.data as *const _ as *const __m256i;
-            [
-                _mm256_loadu_si256(ptr),
-                _mm256_loadu_si256(ptr.add(1)),
-                _mm256_loadu_si256(ptr.add(2)),
-            ]
+        #[test]
+        fn rank_1024(bits in btree_set(0..1024usize, 0..1024), index in 0..=1024usize) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            assert_eq!(bitmap.rank(index), bits.range(..index).count());
         }
-    }
 
-    impl Bitmap<1024> {
-        #[target_feature(enable = "sse2")]
-        pub unsafe fn load_m128i(&self) -> [__m128i; 8] {
-            let ptr = &self.data as *const _ as *const __m128i;
-            [
-                _mm_loadu_si128(ptr),
-                _mm_loadu_si128(ptr.add(1)),
-                _mm_loadu_si128(ptr.add(2)),
-                _mm_loadu_si128(ptr.add(3)),
-                _mm_loadu_si128(ptr.add(4)),
-                _mm_loadu_si128(ptr.add(5)),
-                _mm_loadu_si128(ptr.add(6)),
-                _mm_loadu_si128(ptr.add(7)),
-            ]
+        #[test]
+        fn longest_run_200(bits in btree_set(0..200usize, 0..200)) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let mut ones: Option<(usize, usize)> = None;
+            let mut zeros: Option<(usize, usize)> = None;
+            let mut start = 0;
+            for i in 0..200 {
+                if i == 199 || bitmap.get(i) != bitmap.get(i + 1) {
+                    let longest = if bitmap.get(i) { &mut ones } else { &mut zeros };
+                    if longest.is_none_or(|(_, len)| i + 1 - start > len) {
+                        *longest = Some((start, i + 1 - start));
+                    }
+                    start = i + 1;
+                }
+            }
+            assert_eq!(bitmap.longest_run_of_ones(), ones);
+            assert_eq!(bitmap.longest_run_of_zeros(), zeros);
         }
 
-        #[target_feature(enable = "avx")]
-        pub unsafe fn load_m256i(&self) -> [__m256i; 4] {
-            let ptr = &self.data as *const _ as *const __m256i;
-            [
-                _mm256_loadu_si256(ptr),
-                _mm256_loadu_si256(ptr.add(1)),
-                _mm256_loadu_si256(ptr.add(2)),
-                _mm256_loadu_si256(ptr.add(3)),
-            ]
+        #[test]
+        fn find_zero_run_1024(bits in btree_set(0..1024usize, 0..64), len in 0..200usize) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let expected = (0..=1024 - len).find(|start| bits.range(*start..start + len).next().is_none());
+            assert_eq!(bitmap.find_zero_run(len), expected);
         }
-    }
 
-    impl From<__m128i> for Bitmap<128> {
-        fn from(data: __m128i) -> Self {
-            Self {
-                data: unsafe { core::mem::transmute(data) },
+        #[test]
+        fn retain_200(bits in btree_set(0..200usize, 0..200), modulo in 1..10usize) {
+            let mut bitmap = Bitmap::<200>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
             }
+            let original = bitmap;
+            let mut visited = Bitmap::<200>::new();
+            bitmap.retain(|i| {
+                assert!(!visited.set(i, true));
+                i % modulo == 0
+            });
+            assert_eq!(visited, original);
+            assert!(bitmap.into_iter().eq(bits.iter().cloned().filter(|i| i % modulo == 0)));
         }
-    }
 
-    impl From<__m256i> for Bitmap<256> {
-        fn from(data: __m256i) -> Self {
-            Self {
-                data: unsafe { core::mem::transmute(data) },
+        #[test]
+        fn set_and_clear_many_1024(bits in btree_set(0..1024usize, 0..1024), set in vec(0..1024usize, 0..64), cleared in vec(0..1024usize, 0..64)) {
+            let mut bitmap = Bitmap::<1024>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            bitmap.set_many(&set);
+            assert!(bitmap.into_iter().eq(bits.iter().cloned().chain(set.iter().cloned()).collect::<BTreeSet<_>>()));
+            bitmap.clear_many(&cleared);
+            for i in &cleared {
+                assert!(!bitmap.get(*i));
             }
+            assert_eq!(bitmap.len(), bits.iter().chain(set.iter()).filter(|i| !cleared.contains(i)).collect::<BTreeSet<_>>().len());
         }
-    }
 
-    impl From<Bitmap<128>> for __m128i {
-        fn from(data: Bitmap<128>) -> Self {
-            unsafe { data.load_m128i() }
+        #[test]
+        fn iter_from_both_ends(bits in btree_set(0..300usize, 0..50), fronts in vec(proptest::bool::ANY, 0..60)) {
+            let mut bitmap = Bitmap::<300>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let mut expected: std::collections::VecDeque<usize> = bits.iter().cloned().collect();
+            let mut iter = bitmap.into_iter();
+            for front in fronts {
+                assert_eq!(iter.len(), expected.len());
+                if front {
+                    assert_eq!(iter.next(), expected.pop_front());
+                } else {
+                    assert_eq!(iter.next_back(), expected.pop_back());
+                }
+            }
         }
-    }
 
-    impl From<Bitmap<256>> for __m256i {
-        fn from(data: Bitmap<256>) -> Self {
-            unsafe { data.load_m256i() }
+        #[test]
+        fn ranges_cover_bitmap(bits in btree_set(0..300usize, 0..100)) {
+            let mut bitmap = Bitmap::<300>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let ranges: Vec<_> = bitmap.ranges().collect();
+            assert!(ranges.iter().cloned().flatten().eq(bits.iter().cloned()));
+            assert!(ranges.windows(2).all(|pair| pair[0].end < pair[1].start));
+            let mut reversed: Vec<_> = bitmap.ranges().rev().collect();
+            reversed.reverse();
+            assert_eq!(reversed, ranges);
         }
-    }
 
-    #[cfg(test)]
-    mod test {
-        use super::*;
+        #[test]
+        fn iter_nth(bits in btree_set(0..1000usize, 0..100), n in 0..110usize, m in 0..20usize) {
+            let mut bitmap = Bitmap::<1000>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let mut iter = bitmap.into_iter();
+            let mut expected = bits.iter().cloned();
+            assert_eq!(iter.nth(n), expected.nth(n));
+            assert_eq!(iter.len(), expected.len());
+            assert_eq!(iter.nth(m), expected.nth(m));
+            assert_eq!(iter.clone().count(), expected.clone().count());
+            assert_eq!(iter.clone().last(), expected.clone().next_back());
+            assert_eq!(iter.clone().min(), expected.clone().min());
+            assert_eq!(iter.max(), expected.max());
 
-        struct AlignmentTester<const B: usize>
-        where
-            BitsImpl<B>: Bits,
-        {
-            _byte: u8,
-            bits: Bitmap<B>,
+            let mut small = Bitmap::<20>::new();
+            for i in bits.iter().filter(|&&i| i < 20) {
+                small.set(*i, true);
+            }
+            let expected: Vec<usize> = small.bits().filter(|&(_, bit)| bit).map(|(i, _)| i).collect();
+            assert_eq!(small.into_iter().nth(m), expected.get(m).cloned());
         }
 
         #[test]
-        fn load_128() {
-            let mut t: AlignmentTester<128> = AlignmentTester {
-                _byte: 0,
-                bits: Bitmap::new(),
-            };
-            t.bits.set(5, true);
-            let m = unsafe { t.bits.load_m128i() };
-            let mut bits: Bitmap<128> = m.into();
-            assert!(bits.set(5, false));
-            assert!(bits.is_empty());
+        fn window_counts(bits in btree_set(0..300usize, 0..150), width in 1..310usize) {
+            let mut bitmap = Bitmap::<300>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let counts: Vec<usize> = bitmap.window_counts(width).collect();
+            assert_eq!(counts.len(), (301usize).saturating_sub(width));
+            for (start, count) in counts.into_iter().enumerate() {
+                assert_eq!(count, bits.range(start..start + width).count());
+            }
         }
 
         #[test]
-        fn load_256() {
-            let mut t: AlignmentTester<256> = AlignmentTester {
-                _byte: 0,
-                bits: Bitmap::new(),
+        fn ord_is_numeric(left in vec(proptest::num::u64::ANY, 4), right in vec(proptest::num::u64::ANY, 4)) {
+            // Compare 256 bit bitmaps built from four u64 limbs, least
+            // significant first, against comparing the limbs from the top.
+            let build = |limbs: &[u64]| {
+                let mut bitmap = Bitmap::<256>::new();
+                for (index, limb) in limbs.iter().enumerate() {
+                    bitmap.set_bits(index * 64..(index + 1) * 64, *limb as u128);
+                }
+                bitmap
             };
-            t.bits.set(5, true);
-            let m = unsafe { t.bits.load_m256i() };
-            let mut bits: Bitmap<256> = m.into();
-            assert!(bits.set(5, false));
-            assert!(bits.is_empty());
-        }
-    }
-}
+            let expected = left.iter().rev().cmp(right.iter().rev());
+            assert_eq!(build(&left).cmp(&build(&right)), expected);
+            assert_eq!(build(&left).partial_cmp(&build(&right)), Some(expected));
 
-#[cfg(test)]
-mod test {
-    use super::*;
-    use proptest::collection::btree_set;
-    use proptest::proptest;
+            let small = |limbs: &[u64]| Bitmap::<40>::from_value(limbs[0] & ((1 << 40) - 1));
+            assert_eq!(
+                small(&left).cmp(&small(&right)),
+                (left[0] & ((1 << 40) - 1)).cmp(&(right[0] & ((1 << 40) - 1)))
+            );
+        }
 
-    proptest! {
         #[test]
-        fn get_set_and_iter_61(bits in btree_set(0..61usize, 0..61)) {
-            let mut bitmap = Bitmap::<61>::new();
+        fn for_each_matches_iter(bits in btree_set(0..1000usize, 0..100)) {
+            let mut bitmap = Bitmap::<1000>::new();
             for i in &bits {
                 bitmap.set(*i, true);
             }
-            for i in 0..61 {
-                assert_eq!(bitmap.get(i), bits.contains(&i));
+            let mut indices = Vec::new();
+            bitmap.for_each(|index| indices.push(index));
+            assert!(indices.into_iter().eq(bits.iter().cloned()));
+
+            let mut small = Bitmap::<20>::new();
+            for i in bits.iter().filter(|&&i| i < 20) {
+                small.set(*i, true);
             }
-            assert_eq!(bitmap.first_index(), bits.clone().into_iter().next());
-            assert_eq!(bitmap.last_index(), bits.clone().into_iter().next_back());
-            assert!(bitmap.into_iter().eq(bits.clone().into_iter()));
-            assert!(bitmap.into_iter().rev().eq(bits.into_iter().rev()));
+            let mut indices = Vec::new();
+            small.for_each(|index| indices.push(index));
+            assert!(indices.into_iter().eq(small.into_iter()));
         }
 
         #[test]
-        fn get_set_and_iter_64(bits in btree_set(0..64usize, 0..64)) {
-            let mut bitmap = Bitmap::<64>::new();
+        fn convert_1024(bits in btree_set(0..1024usize, 0..1024)) {
+            let mut bitmap = Bitmap::<1024>::new();
             for i in &bits {
                 bitmap.set(*i, true);
             }
-            for i in 0..64 {
-                assert_eq!(bitmap.get(i), bits.contains(&i));
+            let new_bitmap: Bitmap<1024> = TryFrom::try_from(bitmap.to_le_bytes().as_ref()).expect("Unable to convert bitmap!");
+            assert_eq!(new_bitmap, bitmap);
+        }
+
+        #[test]
+        fn bitwise_ops_1024(left in btree_set(0..1024usize, 0..1024), right in btree_set(0..1024usize, 0..1024)) {
+            let mut a = Bitmap::<1024>::new();
+            for i in &left {
+                a.set(*i, true);
             }
-            assert_eq!(bitmap.first_index(), bits.clone().into_iter().next());
-            assert_eq!(bitmap.last_index(), bits.clone().into_iter().next_back());
-            assert!(bitmap.into_iter().eq(bits.clone().into_iter()));
-            assert!(bitmap.into_iter().rev().eq(bits.into_iter().rev()));
+            let mut b = Bitmap::<1024>::new();
+            for i in &right {
+                b.set(*i, true);
+            }
+            assert!((a & b).into_iter().eq(left.intersection(&right).cloned()));
+            assert!((a | b).into_iter().eq(left.union(&right).cloned()));
+            assert!((a ^ b).into_iter().eq(left.symmetric_difference(&right).cloned()));
+            assert!((a - b).into_iter().eq(left.difference(&right).cloned()));
+            assert_eq!(a.union(b), a | b);
+            assert_eq!(a.intersection(b), a & b);
+            assert_eq!(a.difference(b), a - b);
+            assert_eq!(a.symmetric_difference(b), a ^ b);
+            assert_eq!(a.is_disjoint(b), left.is_disjoint(&right));
+            assert_eq!(a.overlaps(b), !left.is_disjoint(&right));
+            assert_eq!(a.intersection_len(b), left.intersection(&right).count());
+            assert_eq!(a.union_len(b), left.union(&right).count());
+            assert_eq!(a.difference_len(b), left.difference(&right).count());
+            assert_eq!(a.hamming_distance(b), left.symmetric_difference(&right).count());
+            assert_eq!(a.is_subset(b), left.is_subset(&right));
+            assert_eq!(a.is_superset(b), left.is_superset(&right));
+            assert!((a & b).is_subset(a));
+            assert!((a | b).is_superset(b));
+            assert!((!a).into_iter().eq((0..1024).filter(|i| !left.contains(i))));
         }
 
         #[test]
-        fn get_set_and_iter_1024(bits in btree_set(0..1024usize, 0..1024)) {
-            let mut bitmap = Bitmap::<1024>::new();
+        fn invert_10(bits in btree_set(0..10usize, 0..10)) {
+            let mut bitmap = Bitmap::<10>::new();
             for i in &bits {
                 bitmap.set(*i, true);
             }
-            for i in 0..1024 {
-                assert_eq!(bitmap.get(i), bits.contains(&i));
-            }
-            assert_eq!(bitmap.first_index(), bits.clone().into_iter().next());
-            assert_eq!(bitmap.last_index(), bits.clone().into_iter().next_back());
-            assert!(bitmap.into_iter().eq(bits.clone().into_iter()));
-            assert!(bitmap.into_iter().rev().eq(bits.into_iter().rev()));
+            let inverted = bitmap.inverted();
+            assert_eq!(inverted.len(), 10 - bits.len());
+            assert!(inverted.into_iter().eq((0..10).filter(|i| !bits.contains(i))));
+            assert_eq!(!bitmap, inverted);
+            bitmap.invert();
+            assert_eq!(bitmap, inverted);
         }
 
         #[test]
-        fn convert_1024(bits in btree_set(0..1024usize, 0..1024)) {
-            let mut bitmap = Bitmap::<1024>::new();
+        fn invert_200(bits in btree_set(0..200usize, 0..200)) {
+            let mut bitmap = Bitmap::<200>::new();
             for i in &bits {
                 bitmap.set(*i, true);
             }
-            let new_bitmap: Bitmap<1024> = TryFrom::try_from(bitmap.as_bytes()).expect("Unable to convert bitmap!");
-            assert_eq!(new_bitmap, bitmap);
+            let inverted = !bitmap;
+            assert_eq!(inverted.len(), 200 - bits.len());
+            assert_eq!(inverted.last_index(), (0..200).rev().find(|i| !bits.contains(i)));
+        }
+
+        #[test]
+        fn assign_ops_1024(left in btree_set(0..1024usize, 0..1024), right in btree_set(0..1024usize, 0..1024)) {
+            let mut a = Bitmap::<1024>::new();
+            for i in &left {
+                a.set(*i, true);
+            }
+            let mut b = Bitmap::<1024>::new();
+            for i in &right {
+                b.set(*i, true);
+            }
+            let mut and = a;
+            and &= &b;
+            assert_eq!(and, a & b);
+            let mut or = a;
+            or |= &b;
+            assert_eq!(or, a | b);
+            let mut xor = a;
+            xor ^= b;
+            assert_eq!(xor, a ^ b);
+            let mut sub = a;
+            sub -= b;
+            assert_eq!(sub, a - b);
         }
     }
 }
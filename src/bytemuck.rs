@@ -0,0 +1,62 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! bytemuck support.
+//!
+//! These are only available when using the `bytemuck` feature flag.
+
+use ::bytemuck::{Pod, Zeroable};
+
+use crate::bitmap::Bitmap;
+use crate::types::{Bits, BitsImpl};
+
+// SAFETY: a bitmap has the same layout as its backing store, which is always
+// a `bool`, an unsigned integer or an array of `u128`, and the all zero value
+// is the empty bitmap.
+unsafe impl<const SIZE: usize> Zeroable for Bitmap<{ SIZE }> where BitsImpl<{ SIZE }>: Bits {}
+
+// A bitmap is only `Pod` when every bit of its store is inside the bitmap,
+// because any other bit pattern would have bits set past the end of it.
+macro_rules! pod_for {
+    ($($size:expr),*) => {
+        $(
+            // SAFETY: the store is an unsigned integer or an array of `u128`
+            // with no bits past the end of the bitmap, so it has no padding
+            // and every bit pattern is a valid bitmap.
+            unsafe impl Pod for Bitmap<$size> {}
+        )*
+    };
+}
+
+pod_for!(8, 16, 32, 64, 128, 256, 384, 512, 640, 768, 896, 1024);
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "std"))]
+    extern crate std;
+
+    use super::*;
+    use std::prelude::rust_2021::*;
+    use std::vec;
+
+    #[test]
+    fn zeroed() {
+        assert_eq!(Bitmap::<1>::zeroed(), Bitmap::new());
+        assert_eq!(Bitmap::<300>::zeroed(), Bitmap::new());
+    }
+
+    #[test]
+    fn cast() {
+        let words: [u128; 2] = [0b101, 1 << 127];
+        let bitmap: Bitmap<256> = ::bytemuck::cast(words);
+        assert_eq!(bitmap.into_iter().collect::<Vec<_>>(), vec![0, 2, 255]);
+        assert_eq!(::bytemuck::cast::<_, [u128; 2]>(bitmap), words);
+
+        let bytes: [u8; 3] = [0xff, 0x00, 0x0f];
+        let bitmaps: &[Bitmap<8>] = ::bytemuck::cast_slice(&bytes);
+        assert_eq!(bitmaps.len(), 3);
+        assert!(bitmaps[0].is_full());
+        assert_eq!(bitmaps[2].len(), 4);
+    }
+}
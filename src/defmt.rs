@@ -0,0 +1,31 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! defmt support.
+//!
+//! These are only available when using the `defmt` feature flag.
+
+use ::defmt::{Format, Formatter};
+
+use crate::bitmap::Bitmap;
+use crate::types::{Bits, BitsImpl};
+
+/// Format the bitmap as the set of indices of its `true` bits, like the
+/// [`Debug`][core::fmt::Debug] format, eg. `Bitmap<8> {1, 5}`.
+impl<const SIZE: usize> Format for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn format(&self, f: Formatter<'_>) {
+        ::defmt::write!(f, "Bitmap<{=usize}> {{", SIZE);
+        let mut indices = self.into_iter();
+        if let Some(index) = indices.next() {
+            ::defmt::write!(f, "{=usize}", index);
+        }
+        for index in indices {
+            ::defmt::write!(f, ", {=usize}", index);
+        }
+        ::defmt::write!(f, "}}");
+    }
+}
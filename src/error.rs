@@ -0,0 +1,80 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+use core::fmt::{Display, Formatter};
+
+/// The error type for fallible operations on a [`Bitmap`][crate::Bitmap].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Error {
+    /// An index was outside the bounds of the bitmap.
+    OutOfBounds {
+        /// The index which was out of bounds.
+        index: usize,
+        /// The size of the bitmap.
+        size: usize,
+    },
+    /// A slice to be converted into a bitmap had the wrong length.
+    InvalidLength {
+        /// The length the slice should have had.
+        expected: usize,
+        /// The length of the slice.
+        found: usize,
+    },
+    /// A string to be parsed as a bitmap had no digits.
+    Empty,
+    /// A string to be parsed as a bitmap contained a character which isn't
+    /// a digit in its radix.
+    InvalidDigit,
+    /// A string, slice or encoded bitmap to be converted into a bitmap had
+    /// bits set past the end of the bitmap.
+    Overflow {
+        /// The size of the bitmap.
+        size: usize,
+    },
+    /// An encoded bitmap used a version of the format which isn't supported.
+    UnsupportedVersion {
+        /// The version found in the encoded bitmap.
+        version: u8,
+    },
+    /// An encoded bitmap was for a bitmap of a different size.
+    SizeMismatch {
+        /// The size of the bitmap being decoded.
+        expected: usize,
+        /// The size found in the encoded bitmap.
+        found: usize,
+    },
+}
+
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Error::OutOfBounds { index, size } => write!(
+                f,
+                "index {} is out of bounds for a bitmap of size {}",
+                index, size
+            ),
+            Error::InvalidLength { expected, found } => write!(
+                f,
+                "expected a slice of length {}, found length {}",
+                expected, found
+            ),
+            Error::Empty => write!(f, "cannot parse a bitmap from an empty string"),
+            Error::InvalidDigit => write!(f, "invalid digit found in string"),
+            Error::Overflow { size } => {
+                write!(f, "bits are set past the end of a bitmap of size {}", size)
+            }
+            Error::UnsupportedVersion { version } => {
+                write!(f, "unsupported encoding version {}", version)
+            }
+            Error::SizeMismatch { expected, found } => write!(
+                f,
+                "expected an encoded bitmap of size {}, found size {}",
+                expected, found
+            ),
+        }
+    }
+}
+
+impl core::error::Error for Error {}
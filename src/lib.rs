@@ -38,14 +38,60 @@
 //!
 //! In addition, [`Bitmap<U128>`][Bitmap] and [`Bitmap<U256>`][Bitmap] will have
 //! `From` and `Into` implementations for [`__m128i`][m128i] and
-//! [`__m256i`][m256i] respectively.
+//! [`__m256i`][m256i] respectively, and [`Bitmap`][Bitmap]s of size 512, 768
+//! and 1024 can be converted to and from arrays of both.
 //!
 //! Note that alignment is unaffected - your bitmaps will be aligned
 //! appropriately for `u128`, not [`__m128i`][m128i] or [`__m256i`][m256i],
 //! unless you arrange for it to be otherwise. This may affect the performance
 //! of SIMD instructions.
 //!
+//! # Feature Flags
+//!
+//! * `rayon`: [`Bitmap`][Bitmap] implements rayon's `IntoParallelIterator`,
+//!   and gains the [`par_union()`][par_union] and
+//!   [`par_intersection()`][par_intersection] methods for combining many
+//!   bitmaps in parallel.
+//! * `serde`: [`Bitmap`][Bitmap] implements `Serialize` and `Deserialize`,
+//!   as a byte array in little endian order, or as a hexadecimal string for
+//!   human readable formats. The [`serde`][serde] module has helpers to
+//!   choose a representation with `#[serde(with = "...")]`.
+//! * `rkyv`: [`Bitmap`][Bitmap] implements rkyv's `Archive`, `Serialize` and
+//!   `Deserialize`. The [`ArchivedBitmap`][ArchivedBitmap] is stored as a
+//!   little endian byte array, and can be validated and read in place
+//!   without copying.
+//! * `bytemuck`: [`Bitmap`][Bitmap] implements bytemuck's `Zeroable`, and
+//!   also `Pod` when its size fills its backing store exactly, ie. for sizes
+//!   8, 16, 32, 64 and multiples of 128, so it can be cast to and from bytes.
+//! * `zerocopy`: [`Bitmap`][Bitmap] derives zerocopy's `FromZeroes` and
+//!   `AsBytes`, and `Unaligned` for sizes up to 8. It doesn't implement
+//!   `FromBytes`, as not every bit pattern is a valid bitmap; read one from
+//!   bytes with `TryFrom<&[u8]>` instead.
+//! * `arbitrary`: [`Bitmap`][Bitmap] implements `Arbitrary`, so it can be
+//!   used as input to fuzz targets.
+//! * `proptest`: the [`proptest`][proptest] module provides strategies for
+//!   generating bitmaps in property tests. This implies `std`.
+//! * `rand`: [`Bitmap`][Bitmap] gains the [`random()`][random] family of
+//!   constructors, which generate bitmaps from a random number generator,
+//!   and the [`choose()`][choose] method, which picks a random `true` bit.
+//! * `subtle`: [`Bitmap`][Bitmap] implements subtle's `ConstantTimeEq` and
+//!   `ConditionallySelectable`, and gains the [`ct_get()`][ct_get] method, for
+//!   working with secret bitmaps without leaking their contents through
+//!   timing.
+//! * `defmt`: [`Bitmap`][Bitmap] implements `defmt::Format`, printing the
+//!   indices of its `true` bits like the `Debug` format.
+//! * `ufmt`: [`Bitmap`][Bitmap] implements `ufmt::uDisplay` and
+//!   `ufmt::uDebug`, with the same output as `Display` and `Debug`.
+//!
 //! [Bitmap]: struct.Bitmap.html
+//! [par_union]: struct.Bitmap.html#method.par_union
+//! [par_intersection]: struct.Bitmap.html#method.par_intersection
+//! [random]: struct.Bitmap.html#method.random
+//! [choose]: struct.Bitmap.html#method.choose
+//! [ct_get]: struct.Bitmap.html#method.ct_get
+//! [serde]: serde/index.html
+//! [proptest]: proptest/index.html
+//! [ArchivedBitmap]: struct.ArchivedBitmap.html
 //! [load_m128i]: struct.Bitmap.html#method.load_m128i
 //! [load_m256i]: struct.Bitmap.html#method.load_m256i
 //! [m128i]: https://doc.rust-lang.org/core/arch/x86_64/struct.__m128i.html
@@ -53,9 +99,50 @@
 //! [loadu_si256]: https://doc.rust-lang.org/core/arch/x86_64/fn._mm256_loadu_si256.html
 
 mod bitmap;
+mod error;
 mod types;
 
+#[cfg(feature = "rayon")]
+mod rayon;
+
+#[cfg(feature = "serde")]
+pub mod serde;
+
+#[cfg(feature = "rkyv")]
+mod rkyv;
+
+#[cfg(feature = "bytemuck")]
+mod bytemuck;
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+
+#[cfg(feature = "proptest")]
+pub mod proptest;
+
+#[cfg(feature = "rand")]
+mod rand;
+
+#[cfg(feature = "subtle")]
+mod subtle;
+
+#[cfg(feature = "defmt")]
+mod defmt;
+
+#[cfg(feature = "ufmt")]
+mod ufmt;
+
+#[doc(inline)]
+pub use crate::bitmap::{BitRef, Bitmap, Combinations, Cursor, Drain, Iter, Ranges, Subsets};
 #[doc(inline)]
-pub use crate::bitmap::{Bitmap, Iter};
+pub use crate::error::Error;
 #[doc(inline)]
 pub use crate::types::{BitOps, Bits, BitsImpl};
+
+#[cfg(feature = "rayon")]
+#[doc(inline)]
+pub use crate::rayon::ParIter;
+
+#[cfg(feature = "rkyv")]
+#[doc(inline)]
+pub use crate::rkyv::ArchivedBitmap;
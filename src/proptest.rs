@@ -0,0 +1,77 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Proptest strategies for generating bitmaps.
+//!
+//! ```rust
+//! # use bitmaps::Bitmap;
+//! use proptest::proptest;
+//!
+//! proptest! {
+//!     fn inverted_twice(bitmap in bitmaps::proptest::bitmap::<100>()) {
+//!         assert_eq!(bitmap.inverted().inverted(), bitmap);
+//!     }
+//! }
+//! # inverted_twice();
+//! ```
+//!
+//! These are only available when using the `proptest` feature flag.
+
+use ::proptest::collection::{vec, SizeRange};
+use ::proptest::sample::subsequence;
+use ::proptest::strategy::Strategy;
+
+use crate::bitmap::Bitmap;
+use crate::types::{Bits, BitsImpl};
+
+/// A strategy for generating bitmaps of any contents.
+///
+/// Each bit is generated independently, and shrinks towards `false`.
+pub fn bitmap<const SIZE: usize>() -> impl Strategy<Value = Bitmap<SIZE>>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    vec(::proptest::bool::ANY, SIZE).prop_map(|bits| bits.into_iter().collect())
+}
+
+/// A strategy for generating bitmaps with a number of `true` bits inside
+/// `len`.
+///
+/// The `true` bits are placed uniformly, and shrink towards fewer bits with
+/// lower indices.
+///
+/// Panics if `len` is empty, or if its upper bound is larger than `SIZE`.
+pub fn bitmap_with_len<const SIZE: usize>(
+    len: impl Into<SizeRange>,
+) -> impl Strategy<Value = Bitmap<SIZE>>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    subsequence((0..SIZE).collect::<Vec<_>>(), len)
+        .prop_map(|indices| indices.into_iter().collect())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::proptest::proptest;
+
+    proptest! {
+        #[test]
+        fn bitmap_fits(bitmap in bitmap::<300>()) {
+            assert!(bitmap.len() <= 300);
+            assert_eq!(Bitmap::<300>::try_from(bitmap.to_le_bytes().as_ref()), Ok(bitmap));
+        }
+
+        #[test]
+        fn bitmap_with_len_in_range(bitmap in bitmap_with_len::<300>(10..20)) {
+            assert!((10..20).contains(&bitmap.len()));
+        }
+
+        #[test]
+        fn bitmap_with_exact_len(bitmap in bitmap_with_len::<5>(5)) {
+            assert!(bitmap.is_full());
+        }
+    }
+}
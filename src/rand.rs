@@ -0,0 +1,277 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Random bitmaps.
+//!
+//! These are only available when using the `rand` feature flag.
+
+use ::rand::{Rng, RngExt};
+
+use crate::bitmap::Bitmap;
+use crate::types::{BitOps, Bits, BitsImpl};
+
+impl<const SIZE: usize> Bitmap<{ SIZE }>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    /// Construct a bitmap where every bit is `true` or `false` with equal
+    /// probability.
+    ///
+    /// The backing store is filled with random bytes in one go, rather than
+    /// generating each bit separately.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// use rand::SeedableRng;
+    /// let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+    /// let bitmap = Bitmap::<300>::random(&mut rng);
+    /// assert!(bitmap.len() > 100 && bitmap.len() < 200);
+    /// ```
+    pub fn random<R>(rng: &mut R) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        let mut bytes = <BitsImpl<SIZE> as Bits>::Store::to_le_bytes(&Default::default());
+        rng.fill_bytes(bytes.as_mut());
+        Self::from_le_bytes(bytes)
+    }
+
+    /// Construct a bitmap where every bit is `true` with probability
+    /// `density`, independently of the other bits.
+    ///
+    /// This generates at most 64 random bitmaps, regardless of the size of
+    /// the bitmap, and is exact up to a precision of 2<sup>-64</sup>.
+    ///
+    /// Panics if `density` isn't between 0 and 1.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// use rand::SeedableRng;
+    /// let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+    /// let bitmap = Bitmap::<1024>::random_with_density(&mut rng, 0.1);
+    /// assert!(bitmap.len() > 50 && bitmap.len() < 150);
+    /// ```
+    pub fn random_with_density<R>(rng: &mut R, density: f64) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        assert!(
+            (0.0..=1.0).contains(&density),
+            "random_with_density: density {} is not between 0 and 1",
+            density
+        );
+        if density == 1.0 {
+            return Self::ones();
+        }
+        // Combine random bitmaps according to the binary digits of the
+        // density, starting from the least significant: OR-ing in a random
+        // bitmap takes the probability of a bit being set from `p` to
+        // `(1 + p) / 2`, and AND-ing takes it to `p / 2`.
+        let digits = (density * 18_446_744_073_709_551_616.0) as u64;
+        let mut bitmap = Self::new();
+        if digits == 0 {
+            return bitmap;
+        }
+        for digit in digits.trailing_zeros()..64 {
+            let random = Self::random(rng);
+            if digits & (1 << digit) != 0 {
+                bitmap |= random;
+            } else {
+                bitmap &= random;
+            }
+        }
+        bitmap
+    }
+
+    /// Construct a bitmap with exactly `len` `true` bits, chosen uniformly
+    /// from every such bitmap.
+    ///
+    /// This uses Floyd's sampling algorithm, which takes one random number
+    /// per bit chosen and never has to retry. If `len` is more than half the
+    /// size of the bitmap, the `false` bits are chosen instead.
+    ///
+    /// Panics if `len` is larger than `SIZE`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// use rand::SeedableRng;
+    /// let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+    /// let bitmap = Bitmap::<1024>::random_with_popcount(&mut rng, 10);
+    /// assert_eq!(bitmap.len(), 10);
+    /// ```
+    pub fn random_with_popcount<R>(rng: &mut R, len: usize) -> Self
+    where
+        R: Rng + ?Sized,
+    {
+        assert!(
+            len <= SIZE,
+            "random_with_popcount: len {} is larger than the bitmap size {}",
+            len,
+            SIZE
+        );
+        let invert = len > SIZE / 2;
+        let count = if invert { SIZE - len } else { len };
+        let mut bitmap = Self::new();
+        for index in SIZE - count..SIZE {
+            let chosen = rng.random_range(0..=index);
+            if bitmap.get(chosen) {
+                bitmap.set(index, true);
+            } else {
+                bitmap.set(chosen, true);
+            }
+        }
+        if invert {
+            bitmap.invert();
+        }
+        bitmap
+    }
+
+    /// Choose the index of one of the `true` bits in the bitmap, with every
+    /// `true` bit equally likely.
+    ///
+    /// This takes a single random number, and finds the bit it selects by
+    /// skipping over whole words of the backing store at a time.
+    ///
+    /// Returns `None` if the bitmap is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// use rand::SeedableRng;
+    /// let mut rng = rand_xorshift::XorShiftRng::seed_from_u64(1);
+    /// let bitmap = Bitmap::<300>::from_range(100..110);
+    /// let index = bitmap.choose(&mut rng).unwrap();
+    /// assert!(bitmap.get(index));
+    /// assert_eq!(Bitmap::<300>::new().choose(&mut rng), None);
+    /// ```
+    pub fn choose<R>(self, rng: &mut R) -> Option<usize>
+    where
+        R: Rng + ?Sized,
+    {
+        let len = self.len();
+        if len == 0 {
+            return None;
+        }
+        let mut skip = rng.random_range(0..len);
+        let words = self.as_words();
+        let word_size = <BitsImpl<SIZE> as Bits>::Store::bit_size() / words.len();
+        for (word_index, &word) in words.iter().enumerate() {
+            let mut word: u128 = word.into();
+            let count = word.count_ones() as usize;
+            if skip >= count {
+                skip -= count;
+                continue;
+            }
+            for _ in 0..skip {
+                word &= word - 1;
+            }
+            return Some(word_index * word_size + word.trailing_zeros() as usize);
+        }
+        unreachable!("choose: skipped past the last true bit")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::rand::SeedableRng;
+    use rand_xorshift::XorShiftRng;
+
+    #[test]
+    fn random_is_seeded() {
+        let left = Bitmap::<1000>::random(&mut XorShiftRng::seed_from_u64(5));
+        let right = Bitmap::<1000>::random(&mut XorShiftRng::seed_from_u64(5));
+        assert_eq!(left, right);
+        assert!(left.len() > 400 && left.len() < 600);
+    }
+
+    #[test]
+    fn random_masks_tail() {
+        let mut rng = XorShiftRng::seed_from_u64(5);
+        for _ in 0..100 {
+            let bitmap = Bitmap::<300>::random(&mut rng);
+            assert_eq!(
+                Bitmap::<300>::try_from(bitmap.to_le_bytes().as_ref()),
+                Ok(bitmap)
+            );
+            assert!(Bitmap::<1>::random(&mut rng).len() <= 1);
+        }
+    }
+
+    #[test]
+    fn random_with_density() {
+        let mut rng = XorShiftRng::seed_from_u64(5);
+        assert!(Bitmap::<300>::random_with_density(&mut rng, 0.0).is_empty());
+        assert!(Bitmap::<300>::random_with_density(&mut rng, 1.0).is_full());
+        let total: usize = (0..100)
+            .map(|_| Bitmap::<1000>::random_with_density(&mut rng, 0.25).len())
+            .sum();
+        assert!(total > 24_000 && total < 26_000);
+        let total: usize = (0..100)
+            .map(|_| Bitmap::<1000>::random_with_density(&mut rng, 0.9).len())
+            .sum();
+        assert!(total > 89_000 && total < 91_000);
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_with_density_out_of_range() {
+        Bitmap::<300>::random_with_density(&mut XorShiftRng::seed_from_u64(5), 1.5);
+    }
+
+    #[test]
+    fn random_with_popcount() {
+        let mut rng = XorShiftRng::seed_from_u64(5);
+        for len in [0, 1, 2, 150, 151, 299, 300] {
+            let bitmap = Bitmap::<300>::random_with_popcount(&mut rng, len);
+            assert_eq!(bitmap.len(), len);
+        }
+        assert_eq!(Bitmap::<1>::random_with_popcount(&mut rng, 1).len(), 1);
+
+        let mut counts = [0; 10];
+        for _ in 0..10_000 {
+            for index in &Bitmap::<10>::random_with_popcount(&mut rng, 3) {
+                counts[index] += 1;
+            }
+        }
+        assert!(counts.iter().all(|count| *count > 2_700 && *count < 3_300));
+    }
+
+    #[test]
+    #[should_panic]
+    fn random_with_popcount_too_large() {
+        Bitmap::<300>::random_with_popcount(&mut XorShiftRng::seed_from_u64(5), 301);
+    }
+
+    #[test]
+    fn choose() {
+        let mut rng = XorShiftRng::seed_from_u64(5);
+        assert_eq!(Bitmap::<1>::ones().choose(&mut rng), Some(0));
+        assert_eq!(Bitmap::<1>::new().choose(&mut rng), None);
+        assert_eq!(
+            Bitmap::<300>::from_range(299..300).choose(&mut rng),
+            Some(299)
+        );
+
+        let bitmap: Bitmap<300> = [3, 127, 128, 250, 299].into_iter().collect();
+        let mut counts = [0; 300];
+        for _ in 0..10_000 {
+            counts[bitmap.choose(&mut rng).unwrap()] += 1;
+        }
+        for (index, count) in counts.iter().enumerate() {
+            if bitmap.get(index) {
+                assert!(*count > 1_800 && *count < 2_200);
+            } else {
+                assert_eq!(*count, 0);
+            }
+        }
+    }
+}
@@ -0,0 +1,180 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Parallel iterators.
+//!
+//! These are only available when using the `rayon` feature flag.
+
+use ::rayon::iter::plumbing::UnindexedConsumer;
+use ::rayon::prelude::*;
+
+use crate::bitmap::Bitmap;
+use crate::types::{BitOps, Bits, BitsImpl};
+
+/// A parallel iterator over the indices of the `true` bits in a [`Bitmap`].
+///
+/// The bitmap is split between threads at word boundaries, so each word of
+/// the backing store is scanned by a single thread.
+#[derive(Clone, Debug)]
+pub struct ParIter<const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    data: Bitmap<{ SIZE }>,
+}
+
+impl<const SIZE: usize> ParallelIterator for ParIter<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+    <BitsImpl<SIZE> as Bits>::Store: Send + Sync,
+{
+    type Item = usize;
+
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result
+    where
+        C: UnindexedConsumer<Self::Item>,
+    {
+        let data = self.data;
+        let words = data.as_words().len();
+        let word_size = <BitsImpl<SIZE> as Bits>::Store::bit_size() / words;
+        (0..words)
+            .into_par_iter()
+            .flat_map_iter(move |word| {
+                let mut bits: u128 = data.as_words()[word].into();
+                core::iter::from_fn(move || {
+                    if bits == 0 {
+                        return None;
+                    }
+                    let index = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    Some(word * word_size + index)
+                })
+            })
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<const SIZE: usize> IntoParallelIterator for Bitmap<{ SIZE }>
+where
+    BitsImpl<SIZE>: Bits,
+    <BitsImpl<SIZE> as Bits>::Store: Send + Sync,
+{
+    type Item = usize;
+    type Iter = ParIter<SIZE>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        ParIter { data: self }
+    }
+}
+
+impl<const SIZE: usize> IntoParallelIterator for &Bitmap<{ SIZE }>
+where
+    BitsImpl<SIZE>: Bits,
+    <BitsImpl<SIZE> as Bits>::Store: Send + Sync,
+{
+    type Item = usize;
+    type Iter = ParIter<SIZE>;
+
+    fn into_par_iter(self) -> Self::Iter {
+        ParIter { data: *self }
+    }
+}
+
+impl<const SIZE: usize> Bitmap<{ SIZE }>
+where
+    BitsImpl<SIZE>: Bits,
+    <BitsImpl<SIZE> as Bits>::Store: Send + Sync,
+{
+    /// Construct the union of any number of bitmaps in parallel.
+    ///
+    /// The result of an empty input is an empty bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmaps: Vec<Bitmap<1024>> = (0..100).map(|i| Bitmap::from_range(i..i + 1)).collect();
+    /// assert_eq!(Bitmap::par_union(bitmaps), Bitmap::mask(100));
+    /// ```
+    pub fn par_union<I>(bitmaps: I) -> Self
+    where
+        I: IntoParallelIterator<Item = Self>,
+    {
+        bitmaps
+            .into_par_iter()
+            .reduce(Self::new, |left, right| left | right)
+    }
+
+    /// Construct the intersection of any number of bitmaps in parallel.
+    ///
+    /// The result of an empty input is a bitmap with every bit set.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmaps: Vec<Bitmap<1024>> = (0..100).map(|i| Bitmap::from_range(i..1024)).collect();
+    /// assert_eq!(Bitmap::par_intersection(bitmaps), Bitmap::from_range(99..1024));
+    /// ```
+    pub fn par_intersection<I>(bitmaps: I) -> Self
+    where
+        I: IntoParallelIterator<Item = Self>,
+    {
+        bitmaps
+            .into_par_iter()
+            .reduce(Self::ones, |left, right| left & right)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "std"))]
+    extern crate std;
+
+    use super::*;
+    use ::proptest::collection::btree_set;
+    use ::proptest::proptest;
+    use std::prelude::rust_2021::*;
+    use std::vec;
+
+    proptest! {
+        #[test]
+        fn par_iter_matches_iter(bits in btree_set(0..1000usize, 0..200)) {
+            let mut bitmap = Bitmap::<1000>::new();
+            for i in &bits {
+                bitmap.set(*i, true);
+            }
+            let indices: Vec<usize> = bitmap.into_par_iter().collect();
+            assert!(indices.into_iter().eq(bits.iter().cloned()));
+            assert_eq!((&bitmap).into_par_iter().count(), bits.len());
+        }
+    }
+
+    #[test]
+    fn par_iter_small() {
+        let bitmap = Bitmap::<10>::from_range(3..6);
+        let indices: Vec<usize> = bitmap.into_par_iter().collect();
+        assert_eq!(indices, vec![3, 4, 5]);
+        let indices: Vec<usize> = Bitmap::<1>::ones().into_par_iter().collect();
+        assert_eq!(indices, vec![0]);
+        let indices: Vec<usize> = Bitmap::<1024>::ones().into_par_iter().collect();
+        assert!(indices.into_iter().eq(0..1024));
+    }
+
+    #[test]
+    fn par_union_and_intersection() {
+        let bitmaps = vec![
+            Bitmap::<300>::from_range(0..200),
+            Bitmap::from_range(100..300),
+            Bitmap::from_range(150..250),
+        ];
+        assert_eq!(Bitmap::par_union(bitmaps.clone()), Bitmap::ones());
+        assert_eq!(
+            Bitmap::par_intersection(bitmaps),
+            Bitmap::from_range(150..200)
+        );
+        assert_eq!(Bitmap::<300>::par_union(Vec::new()), Bitmap::new());
+        assert_eq!(Bitmap::<300>::par_intersection(Vec::new()), Bitmap::ones());
+    }
+}
@@ -0,0 +1,226 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! rkyv support.
+//!
+//! These are only available when using the `rkyv` feature flag.
+
+use core::fmt::{Debug, Formatter};
+
+use ::rkyv::bytecheck::CheckBytes;
+use ::rkyv::rancor::{Fallible, Source};
+use ::rkyv::traits::NoUndef;
+use ::rkyv::{Archive, Deserialize, Place, Portable, Serialize};
+
+use crate::bitmap::Bitmap;
+use crate::types::{BitOps, Bits, BitsImpl};
+
+/// An archived [`Bitmap`].
+///
+/// The bitmap is stored as a byte array in the layout of
+/// [`Bitmap::to_le_bytes`], so it has no alignment requirement and reads the
+/// same on every target. It can be queried where it lies, eg. in a memory
+/// mapped file, or copied out into a [`Bitmap`] with
+/// [`ArchivedBitmap::to_bitmap`].
+///
+/// Validating an archived bitmap checks that no bits are set past the end
+/// of the bitmap.
+#[repr(transparent)]
+pub struct ArchivedBitmap<const SIZE: usize>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    bytes: <<BitsImpl<SIZE> as Bits>::Store as BitOps>::Bytes,
+}
+
+// SAFETY: `BitOps::Bytes` is always a byte array, which has no padding, no
+// alignment requirement and no endianness.
+unsafe impl<const SIZE: usize> Portable for ArchivedBitmap<SIZE> where BitsImpl<SIZE>: Bits {}
+
+// SAFETY: see above, a byte array has no padding.
+unsafe impl<const SIZE: usize> NoUndef for ArchivedBitmap<SIZE> where BitsImpl<SIZE>: Bits {}
+
+impl<const SIZE: usize> ArchivedBitmap<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    /// Copy the archived bitmap into a [`Bitmap`].
+    #[inline]
+    pub fn to_bitmap(&self) -> Bitmap<SIZE> {
+        Bitmap::from_le_bytes(self.bytes)
+    }
+
+    /// Get the value of the bit at a given index.
+    #[inline]
+    pub fn get(&self, index: usize) -> bool {
+        debug_assert!(index < SIZE);
+        self.bytes.as_ref()[index / 8] & (1 << (index % 8)) != 0
+    }
+
+    /// Count the number of `true` bits in the bitmap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.bytes
+            .as_ref()
+            .iter()
+            .map(|byte| byte.count_ones() as usize)
+            .sum()
+    }
+
+    /// Test if the bitmap contains only `false` bits.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.bytes.as_ref().iter().all(|byte| *byte == 0)
+    }
+}
+
+impl<const SIZE: usize> Debug for ArchivedBitmap<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.to_bitmap(), f)
+    }
+}
+
+impl<const SIZE: usize> PartialEq for ArchivedBitmap<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes.as_ref() == other.bytes.as_ref()
+    }
+}
+
+impl<const SIZE: usize> Eq for ArchivedBitmap<SIZE> where BitsImpl<SIZE>: Bits {}
+
+impl<const SIZE: usize> PartialEq<Bitmap<SIZE>> for ArchivedBitmap<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    fn eq(&self, other: &Bitmap<SIZE>) -> bool {
+        self.to_bitmap() == *other
+    }
+}
+
+impl<const SIZE: usize> PartialEq<ArchivedBitmap<SIZE>> for Bitmap<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    fn eq(&self, other: &ArchivedBitmap<SIZE>) -> bool {
+        *self == other.to_bitmap()
+    }
+}
+
+impl<const SIZE: usize> Archive for Bitmap<SIZE>
+where
+    BitsImpl<SIZE>: Bits,
+{
+    type Archived = ArchivedBitmap<SIZE>;
+    type Resolver = ();
+
+    #[inline]
+    fn resolve(&self, _resolver: Self::Resolver, out: Place<Self::Archived>) {
+        out.write(ArchivedBitmap {
+            bytes: self.to_le_bytes(),
+        });
+    }
+}
+
+impl<S, const SIZE: usize> Serialize<S> for Bitmap<SIZE>
+where
+    S: Fallible + ?Sized,
+    BitsImpl<SIZE>: Bits,
+{
+    #[inline]
+    fn serialize(&self, _serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        Ok(())
+    }
+}
+
+impl<D, const SIZE: usize> Deserialize<Bitmap<SIZE>, D> for ArchivedBitmap<SIZE>
+where
+    D: Fallible + ?Sized,
+    BitsImpl<SIZE>: Bits,
+{
+    #[inline]
+    fn deserialize(&self, _deserializer: &mut D) -> Result<Bitmap<SIZE>, D::Error> {
+        Ok(self.to_bitmap())
+    }
+}
+
+// SAFETY: every byte array is valid, so the only check needed is that the
+// bits past the end of the bitmap are zero.
+unsafe impl<C, const SIZE: usize> CheckBytes<C> for ArchivedBitmap<SIZE>
+where
+    C: Fallible + ?Sized,
+    C::Error: Source,
+    BitsImpl<SIZE>: Bits,
+{
+    unsafe fn check_bytes(value: *const Self, _context: &mut C) -> Result<(), C::Error> {
+        // SAFETY: the caller guarantees that `value` points to enough
+        // initialised bytes, and `Self` has no alignment requirement.
+        let bytes = unsafe { (*value).bytes };
+        Bitmap::<SIZE>::try_from(bytes.as_ref())
+            .map(|_| ())
+            .map_err(C::Error::new)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "std"))]
+    extern crate std;
+
+    use super::*;
+    use ::rkyv::rancor;
+    use std::prelude::rust_2021::*;
+    use std::vec;
+
+    #[test]
+    fn round_trip() {
+        let bitmap: Bitmap<300> = [0, 7, 8, 129, 299].into_iter().collect();
+        let bytes = ::rkyv::to_bytes::<rancor::Error>(&bitmap).unwrap();
+        assert_eq!(bytes.len(), 48);
+
+        let archived = ::rkyv::access::<ArchivedBitmap<300>, rancor::Error>(&bytes).unwrap();
+        assert_eq!(*archived, bitmap);
+        assert_eq!(archived.len(), 5);
+        assert!(archived.get(129));
+        assert!(!archived.get(130));
+        assert!(!archived.is_empty());
+
+        let deserialised = ::rkyv::from_bytes::<Bitmap<300>, rancor::Error>(&bytes).unwrap();
+        assert_eq!(deserialised, bitmap);
+    }
+
+    #[test]
+    fn small() {
+        let bitmap = Bitmap::<1>::ones();
+        let bytes = ::rkyv::to_bytes::<rancor::Error>(&bitmap).unwrap();
+        assert_eq!(bytes.as_slice(), &[1]);
+        assert_eq!(
+            ::rkyv::from_bytes::<Bitmap<1>, rancor::Error>(&bytes).unwrap(),
+            bitmap
+        );
+    }
+
+    #[test]
+    fn unaligned() {
+        let bitmap = Bitmap::<128>::from_value(0x0123_4567_89ab_cdef_0011_2233_4455_6677);
+        let mut bytes = vec![0];
+        bytes.extend_from_slice(&::rkyv::to_bytes::<rancor::Error>(&bitmap).unwrap());
+        let archived = ::rkyv::access::<ArchivedBitmap<128>, rancor::Error>(&bytes[1..]).unwrap();
+        assert_eq!(archived.to_bitmap(), bitmap);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(::rkyv::access::<ArchivedBitmap<12>, rancor::Error>(&[0, 0b11]).is_ok());
+        let error = ::rkyv::access::<ArchivedBitmap<12>, rancor::Error>(&[0, 0b10000]).unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("bits are set past the end of a bitmap of size 12"));
+    }
+}
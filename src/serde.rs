@@ -0,0 +1,340 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Serde support.
+//!
+//! For binary formats, a [`Bitmap`] is serialised as a byte array of exactly
+//! `SIZE.div_ceil(8)` bytes in little endian order, the same as the body of
+//! [`Bitmap::encode`], so the format doesn't depend on the backing store
+//! used for the bitmap. For human readable formats, it's
+//! serialised as a hexadecimal string, as formatted by `{:#x}`. Either way,
+//! deserialising checks that the value fits in the bitmap.
+//!
+//! The [`hex`] and [`indices`] modules can be used with
+//! `#[serde(with = "...")]` to choose a representation regardless of the
+//! format:
+//!
+//! ```rust
+//! # use bitmaps::Bitmap;
+//! # use serde::{Deserialize, Serialize};
+//! #[derive(Serialize, Deserialize)]
+//! struct Permissions {
+//!     #[serde(with = "bitmaps::serde::indices")]
+//!     granted: Bitmap<256>,
+//! }
+//! ```
+//!
+//! These are only available when using the `serde` feature flag.
+
+use core::fmt::Formatter;
+
+use ::serde::de::{Deserialize, Deserializer, Error, SeqAccess, Unexpected, Visitor};
+use ::serde::ser::{Serialize, Serializer};
+
+use crate::bitmap::Bitmap;
+use crate::types::{BitOps, Bits, BitsImpl};
+
+impl<const SIZE: usize> Serialize for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        if serializer.is_human_readable() {
+            hex::serialize(self, serializer)
+        } else {
+            serializer.serialize_bytes(&self.to_le_bytes().as_ref()[..SIZE.div_ceil(8)])
+        }
+    }
+}
+
+/// Accepts a bitmap as either a byte array, a sequence of bytes or a
+/// string, so human readable formats can read back the byte form too.
+struct BitmapVisitor<const SIZE: usize>;
+
+impl<'de, const SIZE: usize> Visitor<'de> for BitmapVisitor<SIZE>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    type Value = Bitmap<SIZE>;
+
+    fn expecting(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+        write!(
+            formatter,
+            "{} bytes or a binary or hexadecimal string for a bitmap of size {}",
+            SIZE.div_ceil(8),
+            SIZE
+        )
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        v.parse().map_err(E::custom)
+    }
+
+    fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+    where
+        E: Error,
+    {
+        Bitmap::from_packed_le_bytes(v).map_err(|_| E::invalid_value(Unexpected::Bytes(v), &self))
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut bytes = <BitsImpl<SIZE> as Bits>::Store::to_le_bytes(&Default::default());
+        let len = SIZE.div_ceil(8);
+        for (index, byte) in bytes.as_mut()[..len].iter_mut().enumerate() {
+            *byte = seq
+                .next_element()?
+                .ok_or_else(|| A::Error::invalid_length(index, &self))?;
+        }
+        if seq.next_element::<u8>()?.is_some() {
+            return Err(A::Error::invalid_length(len + 1, &self));
+        }
+        self.visit_bytes(&bytes.as_ref()[..len])
+    }
+}
+
+impl<'de, const SIZE: usize> Deserialize<'de> for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(BitmapVisitor::<SIZE>)
+        } else {
+            deserializer.deserialize_bytes(BitmapVisitor::<SIZE>)
+        }
+    }
+}
+
+/// Serialise a [`Bitmap`] as a hexadecimal string, as formatted by `{:#x}`.
+///
+/// Deserialising also accepts binary strings, as parsed by `FromStr`.
+pub mod hex {
+    use super::*;
+
+    /// Serialise a bitmap as a hexadecimal string.
+    pub fn serialize<S, const SIZE: usize>(
+        bitmap: &Bitmap<SIZE>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        BitsImpl<SIZE>: Bits,
+    {
+        serializer.collect_str(&format_args!("{:#x}", bitmap))
+    }
+
+    /// Deserialise a bitmap from a hexadecimal or binary string.
+    pub fn deserialize<'de, D, const SIZE: usize>(deserializer: D) -> Result<Bitmap<SIZE>, D::Error>
+    where
+        D: Deserializer<'de>,
+        BitsImpl<SIZE>: Bits,
+    {
+        deserializer.deserialize_str(BitmapVisitor::<SIZE>)
+    }
+}
+
+/// Serialise a [`Bitmap`] as a sequence of the indices of its `true` bits,
+/// in ascending order.
+///
+/// Deserialising accepts the indices in any order, and fails if any is out
+/// of bounds.
+pub mod indices {
+    use super::*;
+
+    struct IndicesVisitor<const SIZE: usize>;
+
+    impl<'de, const SIZE: usize> Visitor<'de> for IndicesVisitor<SIZE>
+    where
+        BitsImpl<{ SIZE }>: Bits,
+    {
+        type Value = Bitmap<SIZE>;
+
+        fn expecting(&self, formatter: &mut Formatter<'_>) -> core::fmt::Result {
+            write!(formatter, "a sequence of indices less than {}", SIZE)
+        }
+
+        fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+        where
+            A: SeqAccess<'de>,
+        {
+            let mut bitmap = Bitmap::new();
+            while let Some(index) = seq.next_element::<usize>()? {
+                if index >= SIZE {
+                    return Err(A::Error::invalid_value(
+                        Unexpected::Unsigned(index as u64),
+                        &self,
+                    ));
+                }
+                bitmap.set(index, true);
+            }
+            Ok(bitmap)
+        }
+    }
+
+    /// Serialise a bitmap as a sequence of the indices of its `true` bits.
+    pub fn serialize<S, const SIZE: usize>(
+        bitmap: &Bitmap<SIZE>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        BitsImpl<SIZE>: Bits,
+    {
+        serializer.collect_seq(bitmap)
+    }
+
+    /// Deserialise a bitmap from a sequence of the indices of its `true`
+    /// bits.
+    pub fn deserialize<'de, D, const SIZE: usize>(deserializer: D) -> Result<Bitmap<SIZE>, D::Error>
+    where
+        D: Deserializer<'de>,
+        BitsImpl<SIZE>: Bits,
+    {
+        deserializer.deserialize_seq(IndicesVisitor::<SIZE>)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use ::serde_test::{
+        assert_de_tokens, assert_de_tokens_error, assert_tokens, Compact, Configure, Readable,
+        Token,
+    };
+
+    #[test]
+    fn bytes() {
+        let bitmap = Bitmap::<12>::from_range(8..10);
+        assert_tokens(&bitmap.compact(), &[Token::Bytes(&[0, 0b11])]);
+        assert_de_tokens(&bitmap.compact(), &[Token::BorrowedBytes(&[0, 0b11])]);
+        assert_de_tokens(
+            &bitmap.compact(),
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U8(0),
+                Token::U8(0b11),
+                Token::SeqEnd,
+            ],
+        );
+
+        const BYTES: [u8; 38] = {
+            let mut bytes = [0; 38];
+            let mut index = 0;
+            while index < 37 {
+                bytes[index] = 0xff;
+                index += 1;
+            }
+            bytes[37] = 0x0f;
+            bytes
+        };
+        assert_tokens(&Bitmap::<300>::ones().compact(), &[Token::Bytes(&BYTES)]);
+        assert_tokens(
+            &Bitmap::<129>::from_range(128..129).compact(),
+            &[Token::Bytes(&[
+                0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 1,
+            ])],
+        );
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        assert_de_tokens_error::<Compact<Bitmap<17>>>(
+            &[Token::Bytes(&[0, 0, 0, 0])],
+            "invalid value: byte array, expected 3 bytes or a binary or hexadecimal string for a bitmap of size 17",
+        );
+        assert_de_tokens_error::<Compact<Bitmap<12>>>(
+            &[Token::Bytes(&[0])],
+            "invalid value: byte array, expected 2 bytes or a binary or hexadecimal string for a bitmap of size 12",
+        );
+        assert_de_tokens_error::<Compact<Bitmap<12>>>(
+            &[Token::Bytes(&[0, 0b10000])],
+            "invalid value: byte array, expected 2 bytes or a binary or hexadecimal string for a bitmap of size 12",
+        );
+        assert_de_tokens_error::<Compact<Bitmap<12>>>(
+            &[Token::Seq { len: Some(1) }, Token::U8(0), Token::SeqEnd],
+            "invalid length 1, expected 2 bytes or a binary or hexadecimal string for a bitmap of size 12",
+        );
+        assert_de_tokens_error::<Compact<Bitmap<12>>>(
+            &[
+                Token::Seq { len: Some(3) },
+                Token::U8(0),
+                Token::U8(0),
+                Token::U8(0),
+                Token::SeqEnd,
+            ],
+            "invalid length 3, expected 2 bytes or a binary or hexadecimal string for a bitmap of size 12",
+        );
+    }
+
+    #[test]
+    fn human_readable() {
+        let bitmap = Bitmap::<12>::from_range(8..10);
+        assert_tokens(&bitmap.readable(), &[Token::Str("0x300")]);
+        assert_de_tokens(&bitmap.readable(), &[Token::Str("0b1100000000")]);
+        assert_de_tokens(&bitmap.readable(), &[Token::Bytes(&[0, 0b11])]);
+        assert_de_tokens_error::<Readable<Bitmap<12>>>(
+            &[Token::Str("0x1000")],
+            "bits are set past the end of a bitmap of size 12",
+        );
+    }
+
+    macro_rules! with_newtype {
+        ($name:ident, $module:ident, $size:expr) => {
+            #[derive(Debug, PartialEq)]
+            struct $name(Bitmap<$size>);
+
+            impl Serialize for $name {
+                fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                    $module::serialize(&self.0, serializer)
+                }
+            }
+
+            impl<'de> Deserialize<'de> for $name {
+                fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                    $module::deserialize(deserializer).map($name)
+                }
+            }
+        };
+    }
+
+    with_newtype!(WithHex, hex, 8);
+    with_newtype!(WithIndices, indices, 300);
+
+    #[test]
+    fn with_hex() {
+        let value = || WithHex(Bitmap::from_value(0xa5));
+        assert_tokens(&value().compact(), &[Token::Str("0xa5")]);
+        assert_tokens(&value().readable(), &[Token::Str("0xa5")]);
+    }
+
+    #[test]
+    fn with_indices() {
+        let value = WithIndices([3, 299].into_iter().collect());
+        assert_tokens(
+            &value,
+            &[
+                Token::Seq { len: Some(2) },
+                Token::U64(3),
+                Token::U64(299),
+                Token::SeqEnd,
+            ],
+        );
+        assert_de_tokens_error::<WithIndices>(
+            &[Token::Seq { len: Some(1) }, Token::U64(300)],
+            "invalid value: integer `300`, expected a sequence of indices less than 300",
+        );
+    }
+}
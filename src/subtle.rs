@@ -0,0 +1,112 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! Constant time operations.
+//!
+//! These are only available when using the `subtle` feature flag.
+
+use ::subtle::{Choice, ConditionallySelectable, ConstantTimeEq};
+
+use crate::bitmap::Bitmap;
+use crate::types::{Bits, BitsImpl};
+
+/// Compares every byte of the bitmaps, rather than stopping at the first
+/// difference like `PartialEq`.
+impl<const SIZE: usize> ConstantTimeEq for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    #[inline]
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.to_le_bytes()
+            .as_ref()
+            .ct_eq(other.to_le_bytes().as_ref())
+    }
+}
+
+/// Selects every byte of the result from either bitmap without branching.
+impl<const SIZE: usize> ConditionallySelectable for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    #[inline]
+    fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+        let mut bytes = a.to_le_bytes();
+        for (byte, other) in bytes.as_mut().iter_mut().zip(b.to_le_bytes().as_ref()) {
+            byte.conditional_assign(other, choice);
+        }
+        Self::from_le_bytes(bytes)
+    }
+}
+
+impl<const SIZE: usize> Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    /// Get the value of the bit at a given index, in constant time.
+    ///
+    /// Every byte of the bitmap is read regardless of `index`, so neither
+    /// the index nor the value of the bit can be learned from the timing or
+    /// the memory access pattern. If `index` is out of bounds, the result is
+    /// `false`.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use bitmaps::Bitmap;
+    /// let bitmap = Bitmap::<300>::from_range(100..110);
+    /// assert!(bool::from(bitmap.ct_get(105)));
+    /// assert!(!bool::from(bitmap.ct_get(299)));
+    /// ```
+    pub fn ct_get(&self, index: usize) -> Choice {
+        let target = index / 8;
+        let mut value = 0u8;
+        for (byte_index, byte) in self.to_le_bytes().as_ref().iter().enumerate() {
+            value.conditional_assign(byte, byte_index.ct_eq(&target));
+        }
+        Choice::from((value >> (index % 8)) & 1)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn ct_eq() {
+        let left = Bitmap::<300>::from_range(100..200);
+        assert!(bool::from(left.ct_eq(&Bitmap::from_range(100..200))));
+        assert!(!bool::from(left.ct_eq(&Bitmap::from_range(100..201))));
+        assert!(bool::from(Bitmap::<1>::ones().ct_eq(&Bitmap::ones())));
+        assert!(!bool::from(Bitmap::<1>::ones().ct_eq(&Bitmap::new())));
+    }
+
+    #[test]
+    fn conditional_select() {
+        let left = Bitmap::<300>::from_range(0..10);
+        let right = Bitmap::<300>::from_range(290..300);
+        assert_eq!(
+            Bitmap::conditional_select(&left, &right, Choice::from(0)),
+            left
+        );
+        assert_eq!(
+            Bitmap::conditional_select(&left, &right, Choice::from(1)),
+            right
+        );
+
+        let mut bitmap = Bitmap::<12>::new();
+        bitmap.conditional_assign(&Bitmap::ones(), Choice::from(1));
+        assert!(bitmap.is_full());
+    }
+
+    #[test]
+    fn ct_get() {
+        let bitmap: Bitmap<300> = [0, 7, 8, 129, 299].into_iter().collect();
+        for index in 0..300 {
+            assert_eq!(bool::from(bitmap.ct_get(index)), bitmap.get(index));
+        }
+        assert!(!bool::from(bitmap.ct_get(1000)));
+        assert!(bool::from(Bitmap::<1>::ones().ct_get(0)));
+    }
+}
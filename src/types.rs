@@ -4,11 +4,34 @@
 
 use core::fmt::Debug;
 
+mod sealed {
+    pub trait Sealed {}
+}
+
 /// A trait that defines generalised operations on a `Bits::Store` type.
-pub trait BitOps {
+///
+/// This trait is sealed: it is only implemented for the backing stores
+/// provided by this crate, so methods can be added to it without breaking
+/// anything downstream.
+pub trait BitOps: sealed::Sealed {
+    /// A byte array the same size as `Self`.
+    type Bytes: Copy + AsRef<[u8]> + AsMut<[u8]>;
+    /// The word type `Self` is made of.
+    type Word: Copy + Into<u128>;
+
     fn get(bits: &Self, index: usize) -> bool;
     fn set(bits: &mut Self, index: usize, value: bool) -> bool;
+    fn toggle(bits: &mut Self, index: usize) -> bool;
+    /// # Safety
+    ///
+    /// `index` must be less than `bit_size()`.
+    unsafe fn get_unchecked(bits: &Self, index: usize) -> bool;
+    /// # Safety
+    ///
+    /// `index` must be less than `bit_size()`.
+    unsafe fn set_unchecked(bits: &mut Self, index: usize, value: bool) -> bool;
     fn len(bits: &Self) -> usize;
+    fn for_each(bits: &Self, f: impl FnMut(usize));
     fn first_index(bits: &Self) -> Option<usize>;
     fn first_false_index(bits: &Self) -> Option<usize>;
     fn last_index(bits: &Self) -> Option<usize>;
@@ -17,17 +40,37 @@ pub trait BitOps {
     fn next_false_index(bits: &Self, index: usize) -> Option<usize>;
     fn prev_index(bits: &Self, index: usize) -> Option<usize>;
     fn prev_false_index(bits: &Self, index: usize) -> Option<usize>;
+    fn overlaps(bits: &Self, other_bits: &Self) -> bool;
+    fn bit_and_len(bits: &Self, other_bits: &Self) -> usize;
+    fn bit_or_len(bits: &Self, other_bits: &Self) -> usize;
+    fn bit_and_not_len(bits: &Self, other_bits: &Self) -> usize;
+    fn bit_xor_len(bits: &Self, other_bits: &Self) -> usize;
     fn bit_and(bits: &mut Self, other_bits: &Self);
     fn bit_or(bits: &mut Self, other_bits: &Self);
     fn bit_xor(bits: &mut Self, other_bits: &Self);
+    fn combine_with(bits: &mut Self, other_bits: &Self, f: impl FnMut(u128, u128) -> u128);
     fn invert(bits: &mut Self);
+    fn reverse(bits: &mut Self);
+    fn shift_left(bits: &mut Self, shift: usize);
+    fn shift_right(bits: &mut Self, shift: usize);
     fn make_mask(shift: usize) -> Self;
+    fn get_bits(bits: &Self, index: usize, len: usize) -> u128;
+    fn set_bits(bits: &mut Self, index: usize, len: usize, value: u128);
     fn bit_size() -> usize;
-    #[cfg(feature = "std")]
-    fn to_hex(bits: &Self) -> String;
+    fn to_le_bytes(bits: &Self) -> Self::Bytes;
+    fn to_be_bytes(bits: &Self) -> Self::Bytes;
+    fn from_le_bytes(bytes: Self::Bytes) -> Self;
+    fn from_be_bytes(bytes: Self::Bytes) -> Self;
+    fn as_words(bits: &Self) -> &[Self::Word];
+    fn as_words_mut(bits: &mut Self) -> &mut [Self::Word];
 }
 
+impl sealed::Sealed for bool {}
+
 impl BitOps for bool {
+    type Bytes = [u8; 1];
+    type Word = bool;
+
     #[inline]
     fn get(bits: &Self, index: usize) -> bool {
         debug_assert!(index == 0);
@@ -40,6 +83,24 @@ impl BitOps for bool {
         core::mem::replace(bits, value)
     }
 
+    #[inline]
+    fn toggle(bits: &mut Self, index: usize) -> bool {
+        debug_assert!(index == 0);
+        let prev = *bits;
+        *bits = !prev;
+        prev
+    }
+
+    #[inline]
+    unsafe fn get_unchecked(bits: &Self, _index: usize) -> bool {
+        *bits
+    }
+
+    #[inline]
+    unsafe fn set_unchecked(bits: &mut Self, _index: usize, value: bool) -> bool {
+        core::mem::replace(bits, value)
+    }
+
     #[inline]
     fn len(bits: &Self) -> usize {
         if *bits {
@@ -49,6 +110,13 @@ impl BitOps for bool {
         }
     }
 
+    #[inline]
+    fn for_each(bits: &Self, mut f: impl FnMut(usize)) {
+        if *bits {
+            f(0);
+        }
+    }
+
     #[inline]
     fn first_index(bits: &Self) -> Option<usize> {
         if *bits {
@@ -85,30 +153,19 @@ impl BitOps for bool {
         }
     }
     #[inline]
-    fn next_index(bits: &Self, index: usize) -> Option<usize> {
-        debug_assert!(index == 0);
-        if *bits {
-            None
-        } else {
-            None
-        }
+    fn next_index(_bits: &Self, _index: usize) -> Option<usize> {
+        None
     }
 
     #[inline]
-    fn next_false_index(bits: &Self, index: usize) -> Option<usize> {
-        debug_assert!(index == 0);
-        if *bits {
-            None
-        } else {
-            None
-        }
+    fn next_false_index(_bits: &Self, _index: usize) -> Option<usize> {
+        None
     }
 
     #[inline]
     fn prev_index(bits: &Self, index: usize) -> Option<usize> {
-        debug_assert!(index == 0);
-        if *bits {
-            None
+        if *bits && index > 0 {
+            Some(0)
         } else {
             None
         }
@@ -116,14 +173,38 @@ impl BitOps for bool {
 
     #[inline]
     fn prev_false_index(bits: &Self, index: usize) -> Option<usize> {
-        debug_assert!(index == 0);
-        if *bits {
-            None
+        if !*bits && index > 0 {
+            Some(0)
         } else {
             None
         }
     }
 
+    #[inline]
+    fn overlaps(bits: &Self, other_bits: &Self) -> bool {
+        *bits && *other_bits
+    }
+
+    #[inline]
+    fn bit_and_len(bits: &Self, other_bits: &Self) -> usize {
+        (*bits && *other_bits) as usize
+    }
+
+    #[inline]
+    fn bit_or_len(bits: &Self, other_bits: &Self) -> usize {
+        (*bits || *other_bits) as usize
+    }
+
+    #[inline]
+    fn bit_and_not_len(bits: &Self, other_bits: &Self) -> usize {
+        (*bits && !*other_bits) as usize
+    }
+
+    #[inline]
+    fn bit_xor_len(bits: &Self, other_bits: &Self) -> usize {
+        (*bits != *other_bits) as usize
+    }
+
     #[inline]
     fn bit_and(bits: &mut Self, other_bits: &Self) {
         *bits &= *other_bits;
@@ -139,33 +220,95 @@ impl BitOps for bool {
         *bits ^= *other_bits;
     }
 
+    #[inline]
+    fn combine_with(bits: &mut Self, other_bits: &Self, mut f: impl FnMut(u128, u128) -> u128) {
+        *bits = f(*bits as u128, *other_bits as u128) & 1 != 0;
+    }
+
     #[inline]
     fn invert(bits: &mut Self) {
         *bits = !*bits;
     }
 
+    #[inline]
+    fn reverse(_bits: &mut Self) {}
+
+    #[inline]
+    fn shift_left(bits: &mut Self, shift: usize) {
+        if shift > 0 {
+            *bits = false;
+        }
+    }
+
+    #[inline]
+    fn shift_right(bits: &mut Self, shift: usize) {
+        if shift > 0 {
+            *bits = false;
+        }
+    }
+
     #[inline]
     fn make_mask(shift: usize) -> Self {
         shift > 0
     }
 
-    #[cfg(feature = "std")]
-    fn to_hex(bits: &Self) -> String {
-        if *bits {
-            "1".to_owned()
-        } else {
-            "0".to_owned()
+    #[inline]
+    fn get_bits(bits: &Self, index: usize, len: usize) -> u128 {
+        debug_assert!(index == 0 || len == 0);
+        (*bits && len > 0) as u128
+    }
+
+    #[inline]
+    fn set_bits(bits: &mut Self, index: usize, len: usize, value: u128) {
+        debug_assert!(index == 0 || len == 0);
+        if len > 0 {
+            *bits = value & 1 != 0;
         }
     }
 
     fn bit_size() -> usize {
         1
     }
+
+    #[inline]
+    fn to_le_bytes(bits: &Self) -> Self::Bytes {
+        [*bits as u8]
+    }
+
+    #[inline]
+    fn to_be_bytes(bits: &Self) -> Self::Bytes {
+        [*bits as u8]
+    }
+
+    #[inline]
+    fn from_le_bytes(bytes: Self::Bytes) -> Self {
+        bytes[0] & 1 != 0
+    }
+
+    #[inline]
+    fn from_be_bytes(bytes: Self::Bytes) -> Self {
+        bytes[0] & 1 != 0
+    }
+
+    #[inline]
+    fn as_words(bits: &Self) -> &[Self::Word] {
+        core::slice::from_ref(bits)
+    }
+
+    #[inline]
+    fn as_words_mut(bits: &mut Self) -> &mut [Self::Word] {
+        core::slice::from_mut(bits)
+    }
 }
 
 macro_rules! bitops_for {
     ($target:ty) => {
+        impl sealed::Sealed for $target {}
+
         impl BitOps for $target {
+            type Bytes = [u8; core::mem::size_of::<$target>()];
+            type Word = $target;
+
             #[inline]
             fn get(bits: &Self, index: usize) -> bool {
                 bits & (1 << index) != 0
@@ -183,11 +326,38 @@ macro_rules! bitops_for {
                 prev != 0
             }
 
+            #[inline]
+            fn toggle(bits: &mut Self, index: usize) -> bool {
+                let mask = 1 << index;
+                let prev = *bits & mask;
+                *bits ^= mask;
+                prev != 0
+            }
+
+            #[inline]
+            unsafe fn get_unchecked(bits: &Self, index: usize) -> bool {
+                Self::get(bits, index)
+            }
+
+            #[inline]
+            unsafe fn set_unchecked(bits: &mut Self, index: usize, value: bool) -> bool {
+                Self::set(bits, index, value)
+            }
+
             #[inline]
             fn len(bits: &Self) -> usize {
                 bits.count_ones() as usize
             }
 
+            #[inline]
+            fn for_each(bits: &Self, mut f: impl FnMut(usize)) {
+                let mut word = *bits;
+                while word != 0 {
+                    f(word.trailing_zeros() as usize);
+                    word &= word - 1;
+                }
+            }
+
             #[inline]
             fn first_index(bits: &Self) -> Option<usize> {
                 if *bits == 0 {
@@ -229,7 +399,8 @@ macro_rules! bitops_for {
                 if *bits == 0 || index >= <$target>::BITS as usize - 1 {
                     None
                 } else {
-                    let intermediate = (*bits & (<$target>::MAX.overflowing_shl(1 + index as u32).0));
+                    let intermediate =
+                        (*bits & (<$target>::MAX.overflowing_shl(1 + index as u32).0));
 
                     if intermediate == 0 {
                         None
@@ -258,6 +429,8 @@ macro_rules! bitops_for {
             fn prev_index(bits: &Self, index: usize) -> Option<usize> {
                 if *bits == 0 || index == 0 {
                     None
+                } else if index >= <$target>::BITS as usize {
+                    Self::last_index(bits)
                 } else {
                     let intermediate = bits & ((1 << index) - 1);
 
@@ -274,16 +447,41 @@ macro_rules! bitops_for {
                 if *bits == <$target>::MAX || index == 0 {
                     None
                 } else {
-                    let intermediate = bits | (<$target>::MAX.overflowing_shl(index as u32 - 1).0);
+                    let intermediate = bits | <$target>::MAX.checked_shl(index as u32).unwrap_or(0);
 
                     if intermediate == <$target>::MAX {
                         None
                     } else {
-                        Some(<$target>::BITS as usize - 1 - (intermediate.leading_zeros() as usize))
+                        Some(<$target>::BITS as usize - 1 - (intermediate.leading_ones() as usize))
                     }
                 }
             }
 
+            #[inline]
+            fn overlaps(bits: &Self, other_bits: &Self) -> bool {
+                *bits & *other_bits != 0
+            }
+
+            #[inline]
+            fn bit_and_len(bits: &Self, other_bits: &Self) -> usize {
+                (*bits & *other_bits).count_ones() as usize
+            }
+
+            #[inline]
+            fn bit_or_len(bits: &Self, other_bits: &Self) -> usize {
+                (*bits | *other_bits).count_ones() as usize
+            }
+
+            #[inline]
+            fn bit_and_not_len(bits: &Self, other_bits: &Self) -> usize {
+                (*bits & !*other_bits).count_ones() as usize
+            }
+
+            #[inline]
+            fn bit_xor_len(bits: &Self, other_bits: &Self) -> usize {
+                (*bits ^ *other_bits).count_ones() as usize
+            }
+
             #[inline]
             fn bit_and(bits: &mut Self, other_bits: &Self) {
                 *bits &= *other_bits;
@@ -299,32 +497,111 @@ macro_rules! bitops_for {
                 *bits ^= *other_bits;
             }
 
+            #[inline]
+            fn combine_with(
+                bits: &mut Self,
+                other_bits: &Self,
+                mut f: impl FnMut(u128, u128) -> u128,
+            ) {
+                *bits = f(*bits as u128, *other_bits as u128) as $target;
+            }
+
             #[inline]
             fn invert(bits: &mut Self) {
                 *bits = !*bits;
             }
 
+            #[inline]
+            fn reverse(bits: &mut Self) {
+                *bits = bits.reverse_bits();
+            }
+
+            #[inline]
+            fn shift_left(bits: &mut Self, shift: usize) {
+                *bits = u32::try_from(shift)
+                    .ok()
+                    .and_then(|shift| bits.checked_shl(shift))
+                    .unwrap_or(0);
+            }
+
+            #[inline]
+            fn shift_right(bits: &mut Self, shift: usize) {
+                *bits = u32::try_from(shift)
+                    .ok()
+                    .and_then(|shift| bits.checked_shr(shift))
+                    .unwrap_or(0);
+            }
+
             #[inline]
             fn make_mask(shift: usize) -> Self {
-                (1 << shift) - 1
+                if shift >= <$target>::BITS as usize {
+                    <$target>::MAX
+                } else {
+                    (1 << shift) - 1
+                }
+            }
+
+            #[inline]
+            fn get_bits(bits: &Self, index: usize, len: usize) -> u128 {
+                let value = bits.checked_shr(index as u32).unwrap_or(0) as u128;
+                value & <u128 as BitOps>::make_mask(len)
             }
 
-            #[cfg(feature = "std")]
-            fn to_hex(bits: &Self) -> String {
-                format!("{:x}", bits)
+            #[inline]
+            fn set_bits(bits: &mut Self, index: usize, len: usize, value: u128) {
+                if len == 0 {
+                    return;
+                }
+                let mask = <u128 as BitOps>::make_mask(len) as $target;
+                *bits = (*bits & !(mask << index)) | ((value as $target & mask) << index);
             }
 
             #[inline]
             fn bit_size() -> usize {
                 <$target>::BITS as usize
             }
+
+            #[inline]
+            fn to_le_bytes(bits: &Self) -> Self::Bytes {
+                bits.to_le_bytes()
+            }
+
+            #[inline]
+            fn to_be_bytes(bits: &Self) -> Self::Bytes {
+                bits.to_be_bytes()
+            }
+
+            #[inline]
+            fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                <$target>::from_le_bytes(bytes)
+            }
+
+            #[inline]
+            fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                <$target>::from_be_bytes(bytes)
+            }
+
+            #[inline]
+            fn as_words(bits: &Self) -> &[Self::Word] {
+                core::slice::from_ref(bits)
+            }
+
+            #[inline]
+            fn as_words_mut(bits: &mut Self) -> &mut [Self::Word] {
+                core::slice::from_mut(bits)
+            }
         }
     };
 }
 
 macro_rules! bitops_for_big {
     ($words:expr) => {
+        impl sealed::Sealed for [u128; $words] {}
+
         impl BitOps for [u128; $words] {
+            type Bytes = [u8; 16 * $words];
+            type Word = u128;
+
             #[inline]
             fn get(bits: &Self, index: usize) -> bool {
                 let word_index = index / 128;
@@ -348,6 +625,35 @@ macro_rules! bitops_for_big {
                 prev != 0
             }
 
+            #[inline]
+            fn toggle(bits: &mut Self, index: usize) -> bool {
+                let word_index = index / 128;
+                let mask = 1 << (index & 127);
+                let bits = &mut bits[word_index];
+                let prev = *bits & mask;
+                *bits ^= mask;
+                prev != 0
+            }
+
+            #[inline]
+            unsafe fn get_unchecked(bits: &Self, index: usize) -> bool {
+                let word = unsafe { *bits.get_unchecked(index / 128) };
+                word & (1 << (index & 127)) != 0
+            }
+
+            #[inline]
+            unsafe fn set_unchecked(bits: &mut Self, index: usize, value: bool) -> bool {
+                let mask = 1 << (index & 127);
+                let bits = unsafe { bits.get_unchecked_mut(index / 128) };
+                let prev = *bits & mask;
+                if value {
+                    *bits |= mask;
+                } else {
+                    *bits &= !mask;
+                }
+                prev != 0
+            }
+
             fn make_mask(shift: usize) -> Self {
                 let word_index = shift / 128;
                 let index = shift & 127;
@@ -364,11 +670,49 @@ macro_rules! bitops_for_big {
                 out
             }
 
+            #[inline]
+            fn get_bits(bits: &Self, index: usize, len: usize) -> u128 {
+                let word_index = index / 128;
+                let shift = index & 127;
+                let mut value = bits[word_index] >> shift;
+                if shift > 0 && word_index + 1 < $words {
+                    value |= bits[word_index + 1] << (128 - shift);
+                }
+                value & <u128 as BitOps>::make_mask(len)
+            }
+
+            #[inline]
+            fn set_bits(bits: &mut Self, index: usize, len: usize, value: u128) {
+                if len == 0 {
+                    return;
+                }
+                let word_index = index / 128;
+                let shift = index & 127;
+                let mask = <u128 as BitOps>::make_mask(len);
+                let value = value & mask;
+                bits[word_index] = (bits[word_index] & !(mask << shift)) | (value << shift);
+                if shift + len > 128 {
+                    let word = &mut bits[word_index + 1];
+                    *word = (*word & !(mask >> (128 - shift))) | (value >> (128 - shift));
+                }
+            }
+
             #[inline]
             fn len(bits: &Self) -> usize {
                 bits.iter().fold(0, |acc, next| acc + next.count_ones()) as usize
             }
 
+            #[inline]
+            fn for_each(bits: &Self, mut f: impl FnMut(usize)) {
+                for (index, part) in bits.iter().enumerate() {
+                    let mut word = *part;
+                    while word != 0 {
+                        f(index * 128 + word.trailing_zeros() as usize);
+                        word &= word - 1;
+                    }
+                }
+            }
+
             #[inline]
             fn first_index(bits: &Self) -> Option<usize> {
                 for (index, part) in bits.iter().enumerate() {
@@ -394,7 +738,7 @@ macro_rules! bitops_for_big {
                 let segment: usize = index / 128;
 
                 if (segment >= bits.len()) {
-                    return None
+                    return None;
                 }
 
                 let intermediate = <u128 as BitOps>::next_index(&bits[segment], index % 128);
@@ -404,7 +748,10 @@ macro_rules! bitops_for_big {
                 } else {
                     for (index, part) in bits[(segment + 1)..].iter().enumerate() {
                         if *part != 0u128 {
-                            return Some(128 * (segment + 1 + index) + <u128 as BitOps>::first_index(part).unwrap());
+                            return Some(
+                                128 * (segment + 1 + index)
+                                    + <u128 as BitOps>::first_index(part).unwrap(),
+                            );
                         }
                     }
 
@@ -417,7 +764,7 @@ macro_rules! bitops_for_big {
                 let segment: usize = index / 128;
 
                 if (segment >= bits.len()) {
-                    return None
+                    return Self::last_index(bits);
                 }
 
                 let intermediate = <u128 as BitOps>::prev_index(&bits[segment], index % 128);
@@ -427,7 +774,9 @@ macro_rules! bitops_for_big {
                 } else {
                     for (index, part) in bits[..segment].iter().enumerate().rev() {
                         if *part != 0u128 {
-                            return Some(<u128 as BitOps>::last_index(part).unwrap() + (128 * index));
+                            return Some(
+                                <u128 as BitOps>::last_index(part).unwrap() + (128 * index),
+                            );
                         }
                     }
                     None
@@ -448,7 +797,9 @@ macro_rules! bitops_for_big {
             fn last_false_index(bits: &Self) -> Option<usize> {
                 for (index, part) in bits.iter().enumerate().rev() {
                     if *part != u128::MAX {
-                        return Some(<u128 as BitOps>::last_false_index(part).unwrap() + (128 * index));
+                        return Some(
+                            <u128 as BitOps>::last_false_index(part).unwrap() + (128 * index),
+                        );
                     }
                 }
                 None
@@ -459,7 +810,7 @@ macro_rules! bitops_for_big {
                 let segment: usize = index / 128;
 
                 if (segment >= bits.len()) {
-                    return None
+                    return None;
                 }
 
                 let intermediate = <u128 as BitOps>::next_false_index(&bits[segment], index % 128);
@@ -469,7 +820,10 @@ macro_rules! bitops_for_big {
                 } else {
                     for (index, part) in bits[(segment + 1)..].iter().enumerate() {
                         if *part != u128::MAX {
-                            return Some(128 * (segment + 1 + index) + <u128 as BitOps>::first_false_index(part).unwrap());
+                            return Some(
+                                128 * (segment + 1 + index)
+                                    + <u128 as BitOps>::first_false_index(part).unwrap(),
+                            );
                         }
                     }
 
@@ -482,7 +836,7 @@ macro_rules! bitops_for_big {
                 let segment: usize = index / 128;
 
                 if (segment >= bits.len()) {
-                    return None
+                    return Self::last_false_index(bits);
                 }
 
                 let intermediate = <u128 as BitOps>::prev_false_index(&bits[segment], index % 128);
@@ -492,7 +846,9 @@ macro_rules! bitops_for_big {
                 } else {
                     for (index, part) in bits[..segment].iter().enumerate().rev() {
                         if *part != u128::MAX {
-                            return Some(<u128 as BitOps>::last_false_index(part).unwrap() + (128 * index));
+                            return Some(
+                                <u128 as BitOps>::last_false_index(part).unwrap() + (128 * index),
+                            );
                         }
                     }
 
@@ -500,6 +856,45 @@ macro_rules! bitops_for_big {
                 }
             }
 
+            #[inline]
+            fn overlaps(bits: &Self, other_bits: &Self) -> bool {
+                bits.iter()
+                    .zip(other_bits.iter())
+                    .any(|(left, right)| left & right != 0)
+            }
+
+            #[inline]
+            fn bit_and_len(bits: &Self, other_bits: &Self) -> usize {
+                bits.iter()
+                    .zip(other_bits.iter())
+                    .fold(0, |acc, (left, right)| acc + (left & right).count_ones())
+                    as usize
+            }
+
+            #[inline]
+            fn bit_or_len(bits: &Self, other_bits: &Self) -> usize {
+                bits.iter()
+                    .zip(other_bits.iter())
+                    .fold(0, |acc, (left, right)| acc + (left | right).count_ones())
+                    as usize
+            }
+
+            #[inline]
+            fn bit_and_not_len(bits: &Self, other_bits: &Self) -> usize {
+                bits.iter()
+                    .zip(other_bits.iter())
+                    .fold(0, |acc, (left, right)| acc + (left & !right).count_ones())
+                    as usize
+            }
+
+            #[inline]
+            fn bit_xor_len(bits: &Self, other_bits: &Self) -> usize {
+                bits.iter()
+                    .zip(other_bits.iter())
+                    .fold(0, |acc, (left, right)| acc + (left ^ right).count_ones())
+                    as usize
+            }
+
             #[inline]
             fn bit_and(bits: &mut Self, other_bits: &Self) {
                 for (left, right) in bits.iter_mut().zip(other_bits.iter()) {
@@ -521,6 +916,17 @@ macro_rules! bitops_for_big {
                 }
             }
 
+            #[inline]
+            fn combine_with(
+                bits: &mut Self,
+                other_bits: &Self,
+                mut f: impl FnMut(u128, u128) -> u128,
+            ) {
+                for (left, right) in bits.iter_mut().zip(other_bits.iter()) {
+                    *left = f(*left, *right);
+                }
+            }
+
             #[inline]
             fn invert(bits: &mut Self) {
                 for chunk in bits.iter_mut() {
@@ -528,19 +934,99 @@ macro_rules! bitops_for_big {
                 }
             }
 
-            #[cfg(feature = "std")]
-            fn to_hex(bits: &Self) -> String {
-                let mut out = String::new();
-                for chunk in bits {
-                    out += &format!("{:x}", chunk);
+            #[inline]
+            fn reverse(bits: &mut Self) {
+                bits.reverse();
+                for chunk in bits.iter_mut() {
+                    *chunk = chunk.reverse_bits();
+                }
+            }
+
+            #[inline]
+            fn shift_left(bits: &mut Self, shift: usize) {
+                let words = shift / 128;
+                let shift = shift & 127;
+                for index in (0..$words).rev() {
+                    let mut chunk = if index >= words {
+                        bits[index - words] << shift
+                    } else {
+                        0
+                    };
+                    if shift > 0 && index > words {
+                        chunk |= bits[index - words - 1] >> (128 - shift);
+                    }
+                    bits[index] = chunk;
+                }
+            }
+
+            #[inline]
+            fn shift_right(bits: &mut Self, shift: usize) {
+                let words = shift / 128;
+                let shift = shift & 127;
+                for index in 0..$words {
+                    let source = index + words;
+                    let mut chunk = if source < $words {
+                        bits[source] >> shift
+                    } else {
+                        0
+                    };
+                    if shift > 0 && source + 1 < $words {
+                        chunk |= bits[source + 1] << (128 - shift);
+                    }
+                    bits[index] = chunk;
                 }
-                out
             }
 
             #[inline]
             fn bit_size() -> usize {
                 (<u128>::BITS * $words) as usize
             }
+
+            #[inline]
+            fn to_le_bytes(bits: &Self) -> Self::Bytes {
+                let mut out = [0; 16 * $words];
+                for (chunk, word) in out.chunks_exact_mut(16).zip(bits.iter()) {
+                    chunk.copy_from_slice(&word.to_le_bytes());
+                }
+                out
+            }
+
+            #[inline]
+            fn to_be_bytes(bits: &Self) -> Self::Bytes {
+                let mut out = [0; 16 * $words];
+                for (chunk, word) in out.chunks_exact_mut(16).zip(bits.iter().rev()) {
+                    chunk.copy_from_slice(&word.to_be_bytes());
+                }
+                out
+            }
+
+            #[inline]
+            fn from_le_bytes(bytes: Self::Bytes) -> Self {
+                let mut out = [0; $words];
+                for (word, chunk) in out.iter_mut().zip(bytes.chunks_exact(16)) {
+                    *word = u128::from_le_bytes(chunk.try_into().unwrap());
+                }
+                out
+            }
+
+            #[inline]
+            fn from_be_bytes(bytes: Self::Bytes) -> Self {
+                let mut out = [0; $words];
+                for (word, chunk) in out.iter_mut().rev().zip(bytes.chunks_exact(16)) {
+                    *word = u128::from_be_bytes(chunk.try_into().unwrap());
+                }
+                out
+            }
+
+            #[inline]
+            fn as_words(bits: &Self) -> &[Self::Word] {
+                bits
+            }
+
+            #[inline]
+            fn as_words_mut(bits: &mut Self) -> &mut [Self::Word] {
+                bits
+            }
         }
     };
 }
@@ -586,22 +1072,16 @@ pub trait Bits {
     /// than VALUE - 1 cannot be set), and even then only for functions that
     /// might seek in that area: that is all forward seeking functions, and
     /// the one seeking backwards from the end (last_false_index).
-    /// prev_false_index is not affected, because the supplied index must be
-    /// valid, and any index lower than that is also valid.
+    /// prev_false_index is only affected when the supplied index is past the
+    /// end of the bitmap.
     #[inline]
     fn corrected_first_false_index(store: &Self::Store) -> Option<usize> {
         let result = Self::Store::first_false_index(store);
 
         if Self::Store::bit_size() == Self::VALUE {
             result
-        } else if let Some(result) = result {
-            if result >= Self::VALUE {
-                None
-            } else {
-                Some(result)
-            }
         } else {
-            None
+            result.filter(|&result| result < Self::VALUE)
         }
     }
 
@@ -616,20 +1096,49 @@ pub trait Bits {
         }
     }
 
+    /// Shifting the underlying data type left can move bits into its trailing
+    /// bits, so we clear them again here.
+    #[inline]
+    fn corrected_shift_left(store: &mut Self::Store, shift: usize) {
+        Self::Store::shift_left(store, shift);
+        if Self::Store::bit_size() != Self::VALUE {
+            Self::Store::bit_and(store, &Self::Store::make_mask(Self::VALUE));
+        }
+    }
+
+    /// Reversing the underlying data type moves the bits to the top of it, so
+    /// we shift them back down past the trailing bits.
+    #[inline]
+    fn corrected_reverse(store: &mut Self::Store) {
+        Self::Store::reverse(store);
+        Self::Store::shift_right(store, Self::Store::bit_size() - Self::VALUE);
+    }
+
+    /// An index past the end of the bitmap would make us seek through the
+    /// trailing bits, so we start at the end of the bitmap instead.
+    #[inline]
+    fn corrected_prev_false_index(store: &Self::Store, index: usize) -> Option<usize> {
+        Self::Store::prev_false_index(store, index.min(Self::VALUE))
+    }
+
+    /// Inverting the underlying data type would also set its trailing bits,
+    /// so we clear them again here.
+    #[inline]
+    fn corrected_invert(store: &mut Self::Store) {
+        Self::Store::invert(store);
+        if Self::Store::bit_size() != Self::VALUE {
+            Self::Store::bit_and(store, &Self::Store::make_mask(Self::VALUE));
+        }
+    }
+
     #[inline]
     fn corrected_next_false_index(store: &Self::Store, index: usize) -> Option<usize> {
         let result = Self::Store::next_false_index(store, index);
 
         if Self::Store::bit_size() == Self::VALUE {
             result
-        } else if let Some(result) = result {
-            if result >= Self::VALUE {
-                None
-            } else {
-                Some(result)
-            }
         } else {
-            None
+            result.filter(|&result| result < Self::VALUE)
         }
     }
 }
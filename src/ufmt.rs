@@ -0,0 +1,82 @@
+// This Source Code Form is subject to the terms of the Mozilla Public
+// License, v. 2.0. If a copy of the MPL was not distributed with this
+// file, You can obtain one at http://mozilla.org/MPL/2.0/.
+
+//! ufmt support.
+//!
+//! These are only available when using the `ufmt` feature flag.
+
+use ::ufmt::{uDebug, uDisplay, uWrite, Formatter};
+
+use crate::bitmap::{Bitmap, LOWER_DIGITS};
+use crate::types::{Bits, BitsImpl};
+
+/// Format the bitmap as a string of `SIZE` `0`s and `1`s, one for each bit,
+/// with the highest index first, like the [`Display`][core::fmt::Display]
+/// format.
+impl<const SIZE: usize> uDisplay for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        let mut buffer = [0; SIZE];
+        f.write_str(self.radix_digits(&mut buffer, 1, LOWER_DIGITS))
+    }
+}
+
+/// Format the bitmap as the set of indices of its `true` bits, like the
+/// [`Debug`][core::fmt::Debug] format.
+impl<const SIZE: usize> uDebug for Bitmap<{ SIZE }>
+where
+    BitsImpl<{ SIZE }>: Bits,
+{
+    fn fmt<W>(&self, f: &mut Formatter<'_, W>) -> Result<(), W::Error>
+    where
+        W: uWrite + ?Sized,
+    {
+        f.write_str("Bitmap<")?;
+        uDisplay::fmt(&SIZE, f)?;
+        f.write_str("> {")?;
+        for (position, index) in self.into_iter().enumerate() {
+            if position > 0 {
+                f.write_str(", ")?;
+            }
+            uDisplay::fmt(&index, f)?;
+        }
+        f.write_str("}")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    #[cfg(not(feature = "std"))]
+    extern crate std;
+
+    use super::*;
+    use ::ufmt::uwrite;
+    use std::format;
+    use std::prelude::rust_2021::*;
+
+    #[test]
+    fn display() {
+        let mut out = String::new();
+        uwrite!(out, "{}", Bitmap::<6>::from_range(0..2)).unwrap();
+        assert_eq!(out, format!("{}", Bitmap::<6>::from_range(0..2)));
+        assert_eq!(out, "000011");
+    }
+
+    #[test]
+    fn debug() {
+        let bitmap: Bitmap<300> = [3, 127, 299].into_iter().collect();
+        let mut out = String::new();
+        uwrite!(out, "{:?}", bitmap).unwrap();
+        assert_eq!(out, format!("{:?}", bitmap));
+
+        out.clear();
+        uwrite!(out, "{:?}", Bitmap::<8>::new()).unwrap();
+        assert_eq!(out, format!("{:?}", Bitmap::<8>::new()));
+    }
+}